@@ -0,0 +1,376 @@
+use crate::{
+    planet::{self, Light, ParallelSafe, Planet, SurfaceDefinition},
+    terrain::Terrain,
+};
+use euclid::{Angle, Length};
+use image::{
+    gif::{GifEncoder, Repeat},
+    Delay, Frame, RgbaImage,
+};
+use std::{
+    hash::Hash,
+    io::{self, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// Animation frames packed into a single image, in rows from left to right
+pub struct SpriteSheet {
+    /// The name of the animation, which is used to name its frames and tag
+    pub name: String,
+
+    pub image: RgbaImage,
+
+    /// The width and height of each frame, in pixels
+    pub frame_size: u32,
+
+    /// How many frames are packed into `image`
+    pub frames: u32,
+
+    /// How many frames are in each row of `image`
+    pub columns: u32,
+
+    /// How long each frame is shown for
+    pub frame_duration: Duration,
+}
+
+/// What changes from one frame of an animation to the next
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum AnimationMode {
+    /// The planet spins one full rotation around its axis
+    #[default]
+    Rotation,
+    /// The light sweeps one full circle around the planet, which stays at its `origin`
+    Phase,
+    /// The planet travels once around its `orbit`, lit more brightly near the star and
+    /// more dimly far from it. Without an orbit, it circles the star at its current
+    /// distance.
+    Orbit,
+}
+
+impl AnimationMode {
+    /// The lowercase name of the mode, e.g., "rotation"
+    pub fn name(self) -> &'static str {
+        match self {
+            AnimationMode::Rotation => "rotation",
+            AnimationMode::Phase => "phase",
+            AnimationMode::Orbit => "orbit",
+        }
+    }
+}
+
+impl std::str::FromStr for AnimationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rotation" => Ok(AnimationMode::Rotation),
+            "phase" => Ok(AnimationMode::Phase),
+            "orbit" => Ok(AnimationMode::Orbit),
+            _ => Err(format!(
+                "unknown animation {:?}, expected rotation, phase or orbit",
+                s
+            )),
+        }
+    }
+}
+
+/// The frames of an animated planet
+pub struct Animation {
+    /// What changes between the frames
+    pub mode: AnimationMode,
+
+    pub frames: Vec<RgbaImage>,
+
+    /// How long each frame is shown for
+    pub frame_duration: Duration,
+}
+
+impl<Kind> Planet<Kind>
+where
+    Kind: SurfaceDefinition + ParallelSafe + Clone + Hash + Eq,
+{
+    /// Renders `frames` frames of `mode`, each `pixels` wide and `pixels` tall and shown
+    /// for `frame_duration`. The terrain is only generated once.
+    pub fn animate(
+        &self,
+        mode: AnimationMode,
+        pixels: u32,
+        frames: u32,
+        frame_duration: Duration,
+        sun: &Option<Light>,
+    ) -> Animation {
+        let frame_count = frames.max(1);
+        let mut terrain = Terrain::generate(self);
+        let distance = Length::new(self.origin.to_vector().length());
+        let angle = Angle::radians(self.origin.y.atan2(self.origin.x));
+
+        let frames = (0..frame_count)
+            .map(|frame| {
+                let fraction = frame as f32 / frame_count as f32;
+                let progress = Angle::two_pi() * fraction;
+                let mut light = *sun;
+                match mode {
+                    AnimationMode::Rotation => terrain.rotation = self.rotation + progress,
+                    // Circle the light around the planet at the star's distance
+                    AnimationMode::Phase => {
+                        let from_light = planet::calculate_origin(angle + progress, distance);
+                        terrain.light_source = self.origin - from_light.to_vector();
+                    }
+                    AnimationMode::Orbit => match &self.orbit {
+                        Some(orbit) => {
+                            let time = orbit.period.mul_f32(fraction);
+                            terrain.origin = orbit.position_at(time);
+                            light = sun.map(|sun| self.light_at(time, sun));
+                        }
+                        None => {
+                            terrain.origin = planet::calculate_origin(angle + progress, distance)
+                        }
+                    },
+                }
+                terrain.generate_planet(pixels, &light).image
+            })
+            .collect();
+
+        Animation {
+            mode,
+            frames,
+            frame_duration,
+        }
+    }
+
+    /// Renders `frames` frames of one full rotation into a sprite sheet, each `pixels`
+    /// wide and `pixels` tall. The animation starts at the planet's current `rotation`
+    /// and takes `period` to complete.
+    pub fn generate_rotation(
+        &self,
+        pixels: u32,
+        frames: u32,
+        period: Duration,
+        sun: &Option<Light>,
+    ) -> SpriteSheet {
+        self.animate(
+            AnimationMode::Rotation,
+            pixels,
+            frames,
+            period / frames.max(1),
+            sun,
+        )
+        .sprite_sheet()
+    }
+}
+
+impl Animation {
+    /// Packs the frames into a sprite sheet
+    pub fn sprite_sheet(&self) -> SpriteSheet {
+        let frame_size = self.frames.first().map(|frame| frame.width()).unwrap_or(0);
+        let mut sheet = SpriteSheet::new(
+            self.mode.name(),
+            frame_size,
+            self.frames.len() as u32,
+            self.frame_duration,
+        );
+        for (index, frame) in self.frames.iter().enumerate() {
+            sheet.set_frame(index as u32, frame);
+        }
+        sheet
+    }
+
+    /// Writes the frames as a looping animated GIF
+    pub fn write_gif<W: io::Write>(&self, writer: W) -> anyhow::Result<()> {
+        let mut encoder = GifEncoder::new(writer);
+        encoder.set_repeat(Repeat::Infinite)?;
+        let delay = Delay::from_numer_denom_ms(self.frame_duration.as_millis() as u32, 1);
+        encoder.encode_frames(
+            self.frames
+                .iter()
+                .map(|frame| Frame::from_parts(frame.clone(), 0, 0, delay)),
+        )?;
+        Ok(())
+    }
+
+    /// Writes the frames as a looping animated PNG
+    pub fn write_apng<W: io::Write>(&self, writer: W) -> anyhow::Result<()> {
+        let (width, height) = self
+            .frames
+            .first()
+            .map(|frame| frame.dimensions())
+            .unwrap_or_default();
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(self.frames.len() as u32, 0)?;
+        let delay = self.frame_duration.as_millis().min(u16::MAX as u128) as u16;
+        encoder.set_frame_delay(delay, 1000)?;
+
+        let mut writer = encoder.write_header()?;
+        for frame in &self.frames {
+            writer.write_image_data(frame.as_raw())?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Saves the animation to `path`, as a GIF if its extension is .gif, an animated PNG
+    /// if it is .apng, or a sprite sheet if it is .png
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("gif") | Some("apng") => {}
+            Some("png") => return Ok(self.sprite_sheet().image.save(path)?),
+            _ => anyhow::bail!(
+                "unknown animation format for {:?}, expected a .gif, .apng or .png file",
+                path
+            ),
+        }
+
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        if extension.as_deref() == Some("gif") {
+            self.write_gif(&mut file)?;
+        } else {
+            self.write_apng(&mut file)?;
+        }
+        file.flush()?;
+        Ok(())
+    }
+}
+
+impl SpriteSheet {
+    /// Creates an empty sheet with room for `frames` frames, packed into a roughly square grid
+    pub fn new(name: &str, frame_size: u32, frames: u32, frame_duration: Duration) -> Self {
+        let columns = (frames as f32).sqrt().ceil().max(1.) as u32;
+        let rows = frames.div_ceil(columns);
+        Self {
+            name: name.to_string(),
+            image: RgbaImage::new(frame_size * columns, frame_size * rows),
+            frame_size,
+            frames,
+            columns,
+            frame_duration,
+        }
+    }
+
+    /// The left and top of `frame` within `image`, in pixels
+    pub fn frame_origin(&self, frame: u32) -> (u32, u32) {
+        (
+            frame % self.columns * self.frame_size,
+            frame / self.columns * self.frame_size,
+        )
+    }
+
+    /// Copies `image` into the slot of `frame`
+    pub fn set_frame(&mut self, frame: u32, image: &RgbaImage) {
+        let (left, top) = self.frame_origin(frame);
+        image::imageops::replace(&mut self.image, image, left, top);
+    }
+
+    /// Describes the frames of this sheet in the JSON format written by Aseprite and
+    /// TexturePacker's "JSON (Array)" export. `image_name` is the file the sheet is saved as.
+    #[cfg(feature = "serialization")]
+    pub fn metadata_json(&self, image_name: &str) -> String {
+        let size = Size {
+            w: self.frame_size,
+            h: self.frame_size,
+        };
+        let frames = (0..self.frames)
+            .map(|frame| {
+                let (x, y) = self.frame_origin(frame);
+                FrameMetadata {
+                    filename: format!("{} {}", self.name, frame),
+                    frame: Rect { x, y, size },
+                    rotated: false,
+                    trimmed: false,
+                    sprite_source_size: Rect { x: 0, y: 0, size },
+                    source_size: size,
+                    duration: self.frame_duration.as_millis() as u64,
+                }
+            })
+            .collect();
+        let metadata = SheetMetadata {
+            frames,
+            meta: Meta {
+                app: env!("CARGO_PKG_NAME"),
+                version: env!("CARGO_PKG_VERSION"),
+                image: image_name,
+                format: "RGBA8888",
+                size: Size {
+                    w: self.image.width(),
+                    h: self.image.height(),
+                },
+                scale: "1",
+                frame_tags: vec![FrameTag {
+                    name: &self.name,
+                    from: 0,
+                    to: self.frames.saturating_sub(1),
+                    direction: "forward",
+                }],
+            },
+        };
+        serde_json::to_string_pretty(&metadata).expect("sheet metadata serializes to JSON")
+    }
+}
+
+#[cfg(feature = "serialization")]
+#[derive(serde::Serialize)]
+struct SheetMetadata<'a> {
+    frames: Vec<FrameMetadata>,
+    meta: Meta<'a>,
+}
+
+#[cfg(feature = "serialization")]
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FrameMetadata {
+    filename: String,
+    frame: Rect,
+    rotated: bool,
+    trimmed: bool,
+    sprite_source_size: Rect,
+    source_size: Size,
+    duration: u64,
+}
+
+#[cfg(feature = "serialization")]
+#[derive(serde::Serialize)]
+struct Meta<'a> {
+    app: &'static str,
+    version: &'static str,
+    image: &'a str,
+    format: &'static str,
+    size: Size,
+    scale: &'static str,
+    #[serde(rename = "frameTags")]
+    frame_tags: Vec<FrameTag<'a>>,
+}
+
+#[cfg(feature = "serialization")]
+#[derive(serde::Serialize)]
+struct FrameTag<'a> {
+    name: &'a str,
+    from: u32,
+    to: u32,
+    direction: &'static str,
+}
+
+#[cfg(feature = "serialization")]
+#[derive(serde::Serialize)]
+struct Rect {
+    x: u32,
+    y: u32,
+    #[serde(flatten)]
+    size: Size,
+}
+
+#[cfg(feature = "serialization")]
+#[derive(serde::Serialize, Clone, Copy)]
+struct Size {
+    w: u32,
+    h: u32,
+}
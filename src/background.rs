@@ -0,0 +1,197 @@
+use crate::star::Star;
+use image::{Rgba, RgbaImage};
+use noise::{NoiseFn, OpenSimplex, Seedable};
+use palette::{Limited, LinSrgb, Mix, Srgb};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Settings for a seeded space backdrop: a starfield, and optionally clouds of nebula
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Background {
+    pub seed: u64,
+
+    /// The color of empty space
+    pub color: Srgb<f32>,
+
+    /// How many stars to place per pixel
+    pub star_density: f32,
+
+    /// Controls how much more common faint stars are than bright ones. A star's brightness
+    /// is a uniform random value raised to this power, so larger values produce fewer
+    /// bright stars.
+    pub magnitude_exponent: f32,
+
+    /// Stars at least this bright glow into the neighboring pixels
+    pub glow_threshold: f32,
+
+    /// Clouds of gas drawn behind the stars
+    pub nebula: Option<Nebula>,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            color: Srgb::new(5u8, 6, 14).into_format(),
+            star_density: 0.003,
+            magnitude_exponent: 3.,
+            glow_threshold: 0.8,
+            nebula: None,
+        }
+    }
+}
+
+/// Settings for clouds of gas made from domain-warped noise
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Nebula {
+    /// The color of the thinnest parts of the clouds
+    pub color: Srgb<f32>,
+
+    /// The color of the densest parts of the clouds
+    pub secondary_color: Srgb<f32>,
+
+    /// The size of the largest features, in pixels
+    pub scale: f32,
+
+    /// How far the noise is pushed around by itself, in multiples of `scale`. Larger
+    /// values produce more swirling, filament-like clouds.
+    pub warp: f32,
+
+    /// How much of the image is covered by clouds, from 0.0 to 1.0
+    pub coverage: f32,
+
+    /// The opacity of the densest parts of the clouds
+    pub opacity: f32,
+}
+
+impl Default for Nebula {
+    fn default() -> Self {
+        Self {
+            color: Srgb::new(58u8, 32, 110).into_format(),
+            secondary_color: Srgb::new(196u8, 74, 128).into_format(),
+            scale: 96.,
+            warp: 1.5,
+            coverage: 0.5,
+            opacity: 0.6,
+        }
+    }
+}
+
+/// How many layers of noise make up the nebula
+const NEBULA_OCTAVES: u32 = 5;
+
+impl Background {
+    /// A starfield with a nebula, both generated from `seed`
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            nebula: Some(Nebula::default()),
+            ..Default::default()
+        }
+    }
+
+    /// Generates an opaque image `width` by `height` pixels. The nebula's features have the
+    /// same size at any resolution, so larger images show more of the same backdrop.
+    pub fn generate(&self, width: u32, height: u32) -> RgbaImage {
+        let space = self.color.into_linear();
+        let mut colors = vec![space; (width * height) as usize];
+
+        if let Some(nebula) = &self.nebula {
+            nebula.draw(self.seed, width, &mut colors);
+        }
+        self.draw_stars(width, height, &mut colors);
+
+        RgbaImage::from_fn(width, height, |x, y| {
+            let color = Srgb::from_linear(colors[(y * width + x) as usize]).into_format::<u8>();
+            Rgba([color.red, color.green, color.blue, 255])
+        })
+    }
+
+    /// Draws `foreground`, such as a generated planet, over a background of the same size
+    pub fn composite(&self, foreground: &RgbaImage) -> RgbaImage {
+        let mut image = self.generate(foreground.width(), foreground.height());
+        image::imageops::overlay(&mut image, foreground, 0, 0);
+        image
+    }
+
+    fn draw_stars(&self, width: u32, height: u32, colors: &mut [LinSrgb<f32>]) {
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let count = ((width * height) as f32 * self.star_density.max(0.)).round() as u32;
+        let mut add = |x: i64, y: i64, color: LinSrgb<f32>| {
+            if x >= 0 && y >= 0 && x < width as i64 && y < height as i64 {
+                let pixel = &mut colors[(y as u32 * width + x as u32) as usize];
+                *pixel = Limited::clamp(&(*pixel + color));
+            }
+        };
+        for _ in 0..count {
+            let x = rng.gen_range(0..width) as i64;
+            let y = rng.gen_range(0..height) as i64;
+            let brightness = rng.gen::<f32>().powf(self.magnitude_exponent);
+            // Like the stars themselves, cool colors are more common than hot ones
+            let temperature = 3_000. + 12_000. * rng.gen::<f32>().powi(2);
+            let color = Star::with_temperature(temperature).color().into_linear() * brightness;
+
+            add(x, y, color);
+            if brightness >= self.glow_threshold {
+                let glow = color * 0.3;
+                add(x - 1, y, glow);
+                add(x + 1, y, glow);
+                add(x, y - 1, glow);
+                add(x, y + 1, glow);
+            }
+        }
+    }
+}
+
+impl Nebula {
+    fn draw(&self, seed: u64, width: u32, colors: &mut [LinSrgb<f32>]) {
+        let noise = OpenSimplex::new().set_seed(seed as u32);
+        let fbm = |x: f64, y: f64| {
+            let mut value = 0.;
+            let mut amplitude = 0.5;
+            let mut frequency = 1.;
+            for _ in 0..NEBULA_OCTAVES {
+                value += noise.get([x * frequency, y * frequency]) * amplitude;
+                amplitude *= 0.5;
+                frequency *= 2.;
+            }
+            value as f32
+        };
+
+        let scale = self.scale.max(1.) as f64;
+        let warp = self.warp as f64;
+        let coverage = self.coverage.clamp(0.01, 1.);
+        let color = self.color.into_linear();
+        let secondary_color = self.secondary_color.into_linear();
+        for (index, pixel) in colors.iter_mut().enumerate() {
+            let x = (index as u32 % width) as f64 / scale;
+            let y = (index as u32 / width) as f64 / scale;
+
+            // Offsetting the point by the noise itself gives the clouds their swirls
+            let warp_x = fbm(x + 5.2, y + 1.3) as f64 * warp;
+            let warp_y = fbm(x + 1.7, y + 9.2) as f64 * warp;
+            let value = fbm(x + warp_x, y + warp_y) * 2. + 0.5;
+            let density = ((value - (1. - coverage)) / coverage).clamp(0., 1.);
+            if density <= 0. {
+                continue;
+            }
+
+            let cloud = color.mix(&secondary_color, density);
+            *pixel = pixel.mix(&cloud, density.powf(1.5) * self.opacity.clamp(0., 1.));
+        }
+    }
+}
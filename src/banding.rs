@@ -0,0 +1,50 @@
+use crate::types::UnitSphere;
+use euclid::Vector3D;
+
+/// Settings for stretching the surface noise along lines of latitude into bands, like the
+/// clouds of a gas giant
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Banding {
+    /// How many times more often the surface changes north to south. Larger values produce
+    /// more, narrower bands.
+    pub frequency: f32,
+
+    /// How many times wider features are east to west, which draws the bands out into
+    /// long stripes
+    pub stretch: f32,
+
+    /// How far the bands meander north and south, relative to the radius
+    pub turbulence: f32,
+}
+
+impl Default for Banding {
+    fn default() -> Self {
+        Self {
+            frequency: 5.,
+            stretch: 4.,
+            turbulence: 0.08,
+        }
+    }
+}
+
+impl Banding {
+    /// Stretches `surface_point` so that noise sampled at it forms bands of latitude,
+    /// after shifting it north or south by `swirl` times the turbulence
+    pub(crate) fn banded_point(
+        &self,
+        surface_point: Vector3D<f32, UnitSphere>,
+        swirl: f32,
+    ) -> Vector3D<f32, UnitSphere> {
+        let stretch = self.stretch.max(1.);
+        Vector3D::new(
+            surface_point.x / stretch,
+            (surface_point.y + swirl * self.turbulence) * self.frequency,
+            surface_point.z / stretch,
+        )
+    }
+}
@@ -0,0 +1,72 @@
+use crate::types::Pixels;
+use euclid::Point2D;
+
+/// The image a planet is rendered into, and where the planet sits within it
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Canvas {
+    /// The width of the image, in pixels
+    pub width: u32,
+
+    /// The height of the image, in pixels
+    pub height: u32,
+
+    /// The diameter of the planet, in pixels. If None, the planet fills the shorter side
+    /// of the image, less `padding`. Stars and irregular bodies are drawn slightly smaller
+    /// than this, to leave room for their corona or lumps.
+    pub diameter: Option<u32>,
+
+    /// Where the center of the planet is, measured from the top left of the image. If
+    /// None, the planet is centered.
+    pub center: Option<Point2D<f32, Pixels>>,
+
+    /// The space left empty between the planet and the shorter side of the image, in
+    /// pixels, when `diameter` is None
+    pub padding: u32,
+}
+
+impl Default for Canvas {
+    fn default() -> Self {
+        Self::square(128)
+    }
+}
+
+impl Canvas {
+    /// A square image `pixels` wide that the planet fills edge to edge
+    pub fn square(pixels: u32) -> Self {
+        Self {
+            width: pixels,
+            height: pixels,
+            diameter: None,
+            center: None,
+            padding: 0,
+        }
+    }
+
+    /// A square image that fits a planet `diameter` pixels wide with `padding` pixels on
+    /// every side
+    pub fn padded(diameter: u32, padding: u32) -> Self {
+        Self {
+            padding,
+            ..Self::square(diameter + padding * 2)
+        }
+    }
+
+    /// The diameter of the planet, in pixels
+    pub fn diameter(&self) -> f32 {
+        match self.diameter {
+            Some(diameter) => diameter as f32,
+            None => self.width.min(self.height).saturating_sub(self.padding * 2) as f32,
+        }
+    }
+
+    /// The center of the planet, measured from the top left of the image
+    pub fn center(&self) -> Point2D<f32, Pixels> {
+        self.center
+            .unwrap_or_else(|| Point2D::new(self.width as f32 / 2., self.height as f32 / 2.))
+    }
+}
@@ -1,10 +1,12 @@
-use crate::{coloring::Earthlike, planet::Planet};
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 
 pub(crate) mod args;
+pub(crate) mod definition;
 
-use args::{Args, Command, Generate, Lightable, PlanetCommand};
+use crate::background::Background;
+use args::{Animate, Args, Command, Generate, Lightable};
+use rand::{thread_rng, Rng};
 
 pub fn run() -> anyhow::Result<()> {
     let args = Args::from_args();
@@ -12,16 +14,53 @@ pub fn run() -> anyhow::Result<()> {
         #[cfg(feature = "editor")]
         Command::Edit(edit) => crate::editor::run(edit),
         Command::Generate(command) => generate(command),
+        Command::Animate(command) => animate(command),
+        Command::System(command) => system(command),
     }
 }
 
 fn generate(options: Generate) -> anyhow::Result<()> {
     loop {
-        let planet: Planet<Earthlike> = match options.command.clone().unwrap_or_default() {
-            PlanetCommand::New(planet_options) => planet_options.into(),
+        let mut definition = options
+            .command
+            .clone()
+            .unwrap_or_default()
+            .into_definition()?;
+        if let Some(light) = options.light() {
+            definition.light = Some(light);
+        }
+        if let Some(resolution) = options.resolution {
+            definition.resolution = Some(resolution);
+        }
+        if let Some(quantize) = options.quantize()? {
+            definition.quantize = Some(quantize);
+        }
+
+        if let Some(path) = &options.save {
+            definition.save(path)?;
+        }
+
+        let (planet, light) = match options.time {
+            Some(seconds) => {
+                let time = Duration::try_from_secs_f64(seconds)?;
+                (
+                    definition.planet.at_time(time),
+                    definition
+                        .light
+                        .map(|light| definition.planet.light_at(time, light)),
+                )
+            }
+            None => (definition.planet, definition.light),
         };
 
-        let generated = planet.generate(options.resolution.unwrap_or(128), &options.light());
+        let canvas = options.canvas(definition.resolution);
+        let mut generated = planet.generate_canvas(&canvas, &light);
+        if options.background {
+            generated.image = Background::new(planet.seed).composite(&generated.image);
+        }
+        if let Some(quantize) = &definition.quantize {
+            quantize.apply(&mut generated.image);
+        }
 
         generated.image.save(
             options
@@ -41,3 +80,87 @@ fn generate(options: Generate) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+fn animate(options: Animate) -> anyhow::Result<()> {
+    let mut definition = options
+        .command
+        .clone()
+        .unwrap_or_default()
+        .into_definition()?;
+    if let Some(light) = options.light() {
+        definition.light = Some(light);
+    }
+
+    let frame_duration = match options.delay {
+        Some(delay) => Duration::from_millis(delay),
+        None => options.period / options.frames.max(1),
+    };
+    let mut animation = definition.planet.animate(
+        options.mode,
+        options.resolution.or(definition.resolution).unwrap_or(128),
+        options.frames,
+        frame_duration,
+        &definition.light,
+    );
+    if let Some(quantize) = &definition.quantize {
+        for frame in &mut animation.frames {
+            quantize.apply(frame);
+        }
+    }
+
+    let output = options
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("{}.png", options.mode.name())));
+    animation.save(&output)?;
+
+    // Sprite sheets are described by a JSON file next to them
+    let is_sprite_sheet = output
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+    if !is_sprite_sheet {
+        return Ok(());
+    }
+
+    let sheet = animation.sprite_sheet();
+    let metadata = options
+        .metadata
+        .clone()
+        .unwrap_or_else(|| output.with_extension("json"));
+    let image_name = output
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    std::fs::write(&metadata, sheet.metadata_json(&image_name))?;
+
+    Ok(())
+}
+
+fn system(options: args::System) -> anyhow::Result<()> {
+    let seed = options.seed.unwrap_or_else(|| thread_rng().gen());
+    let system = crate::system::System::generate(seed);
+    if let Some(path) = &options.save {
+        definition::save(&system, path)?;
+    }
+
+    let time = Duration::try_from_secs_f64(options.time.unwrap_or_default())?;
+    let overview = system.render_overview(options.resolution.unwrap_or(128), time);
+    overview.save(
+        options
+            .output
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("system.png")),
+    )?;
+
+    println!("Generated system {}:", seed);
+    for body in &system.bodies {
+        println!(
+            "{:?}, {:.2} AU, {:.0} km radius",
+            body.kind,
+            body.distance().get() / crate::system::ASTRONOMICAL_UNIT,
+            body.planet.radius.get()
+        );
+    }
+
+    Ok(())
+}
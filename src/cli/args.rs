@@ -1,10 +1,20 @@
+use super::definition::{self, PlanetDefinition};
 use crate::{
-    coloring::{Earthlike, ElevationColor},
+    animation::AnimationMode,
+    canvas::Canvas,
+    coloring::{DynamicKind, ElevationColor, Palette, PaletteFormat},
+    craters::Craters,
     planet::{self, Light, Planet},
+    quantize::{Dithering, Quantize},
+    shape::Shape,
     types::Kilometers,
 };
+use anyhow::Context;
 use rand::{thread_rng, Rng};
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use structopt::StructOpt;
 
 use euclid::{Angle, Length};
@@ -20,6 +30,17 @@ pub struct Args {
 #[derive(Debug, StructOpt, PartialEq, Clone)]
 pub enum PlanetCommand {
     New(NewPlanetOptions),
+    Load(LoadPlanetOptions),
+}
+
+impl PlanetCommand {
+    /// Creates or loads the planet definition this command describes
+    pub fn into_definition(self) -> anyhow::Result<PlanetDefinition<DynamicKind>> {
+        match self {
+            PlanetCommand::New(options) => Ok(options.planet()?.into()),
+            PlanetCommand::Load(options) => PlanetDefinition::load(&options.path),
+        }
+    }
 }
 
 impl Default for PlanetCommand {
@@ -33,6 +54,8 @@ pub enum Command {
     #[cfg(feature = "editor")]
     Edit(Edit),
     Generate(Generate),
+    Animate(Animate),
+    System(System),
 }
 
 #[cfg(feature = "editor")]
@@ -70,6 +93,10 @@ pub struct Generate {
     #[structopt(short, long)]
     pub output: Option<PathBuf>,
 
+    /// Save the planet's definition to a .ron, .toml or .json file
+    #[structopt(short, long)]
+    pub save: Option<PathBuf>,
+
     /// Render resolution, in pixels
     #[structopt(short = "p", long)]
     pub resolution: Option<u32>,
@@ -78,6 +105,77 @@ pub struct Generate {
     #[structopt(short, long)]
     pub repeat: Option<f32>,
 
+    /// Reduce the image to the colors of a .gpl, .hex or .pal palette file, or "pico8"
+    #[structopt(short, long)]
+    pub quantize: Option<String>,
+
+    /// When quantizing, how to dither: none, floyd-steinberg or ordered
+    #[structopt(long, default_value = "none")]
+    pub dither: Dithering,
+
+    /// Render the planet at this time on the simulation clock, in seconds, following its
+    /// orbit and rotation period
+    #[structopt(short, long)]
+    pub time: Option<f64>,
+
+    /// Draw the planet over a starfield and nebula generated from the planet's seed
+    #[structopt(short, long)]
+    pub background: bool,
+
+    /// Width of the image, in pixels. Defaults to the resolution plus padding.
+    #[structopt(long)]
+    pub width: Option<u32>,
+
+    /// Height of the image, in pixels. Defaults to the resolution plus padding.
+    #[structopt(long)]
+    pub height: Option<u32>,
+
+    /// Empty space around the planet, in pixels
+    #[structopt(long, default_value = "0")]
+    pub padding: u32,
+
+    #[structopt(subcommand)]
+    pub command: Option<PlanetCommand>,
+
+    /// Simulate sun lighting, using the hexadecimal color.
+    pub sun_color: Option<String>,
+
+    /// If simulating the sun, how intense should the light be?
+    pub sols: Option<f32>,
+}
+
+#[derive(Debug, Default, StructOpt, PartialEq)]
+pub struct Animate {
+    /// Where to save the animation, defaults to the mode's name with a .png extension.
+    /// A .gif or .apng file is saved as an animation, and a .png file as a sprite sheet.
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Where to save a sprite sheet's Aseprite JSON metadata, defaults to the output
+    /// path with a .json extension
+    #[structopt(short, long)]
+    pub metadata: Option<PathBuf>,
+
+    /// What changes from frame to frame: rotation, phase or orbit
+    #[structopt(long, default_value = "rotation")]
+    pub mode: AnimationMode,
+
+    /// Render resolution of each frame, in pixels
+    #[structopt(short = "p", long)]
+    pub resolution: Option<u32>,
+
+    /// How many frames make up the animation
+    #[structopt(short, long, default_value = "32")]
+    pub frames: u32,
+
+    /// How long the whole animation takes, in seconds
+    #[structopt(long, default_value = "4", parse(try_from_str = parse_period))]
+    pub period: Duration,
+
+    /// How long each frame is shown, in milliseconds. Overrides the period.
+    #[structopt(short, long)]
+    pub delay: Option<u64>,
+
     #[structopt(subcommand)]
     pub command: Option<PlanetCommand>,
 
@@ -88,6 +186,29 @@ pub struct Generate {
     pub sols: Option<f32>,
 }
 
+#[derive(Debug, Default, StructOpt, PartialEq)]
+pub struct System {
+    /// Where to save the overview image, defaults to system.png
+    #[structopt(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Save the system's definition to a .ron, .toml or .json file
+    #[structopt(short, long)]
+    pub save: Option<PathBuf>,
+
+    /// The seed to generate the system from. Defaults to a random seed.
+    #[structopt(long)]
+    pub seed: Option<u64>,
+
+    /// Height of the star in the overview, in pixels
+    #[structopt(short = "p", long)]
+    pub resolution: Option<u32>,
+
+    /// Render the bodies at this time on the simulation clock, in seconds
+    #[structopt(short, long)]
+    pub time: Option<f64>,
+}
+
 pub trait Lightable {
     fn sun_color_hex(&self) -> &'_ Option<String>;
     fn sols(&self) -> &'_ Option<f32>;
@@ -123,6 +244,16 @@ impl Lightable for Edit {
     }
 }
 
+impl Lightable for Animate {
+    fn sun_color_hex(&self) -> &'_ Option<String> {
+        &self.sun_color
+    }
+
+    fn sols(&self) -> &'_ Option<f32> {
+        &self.sols
+    }
+}
+
 impl Lightable for Generate {
     fn sun_color_hex(&self) -> &'_ Option<String> {
         &self.sun_color
@@ -146,10 +277,24 @@ pub struct NewPlanetOptions {
     /// Planet's radius, in kilometers
     #[structopt(short, long)]
     radius: Option<f32>,
+
+    /// A palette of surface kinds in a .ron, .toml or .json file. Defaults to an
+    /// earthlike palette.
+    #[structopt(short, long, conflicts_with = "preset")]
+    palette: Option<PathBuf>,
+
+    /// A built-in palette: earthlike, mars, europa, io, ocean, venus, moon, jungle or jupiter
+    #[structopt(long)]
+    preset: Option<String>,
+
+    /// Shape the planet like an irregular, cratered asteroid instead of a sphere
+    #[structopt(long)]
+    asteroid: bool,
 }
 
-impl Into<Planet<Earthlike>> for NewPlanetOptions {
-    fn into(self) -> Planet<Earthlike> {
+impl NewPlanetOptions {
+    /// Creates a planet with a random seed
+    pub fn planet(self) -> anyhow::Result<Planet<DynamicKind>> {
         let distance = Length::<f32, Kilometers>::new(self.distance.unwrap_or(150_200_000.));
         let radius = Length::new(self.radius.unwrap_or(6_371.));
         let origin = planet::calculate_origin(
@@ -158,7 +303,80 @@ impl Into<Planet<Earthlike>> for NewPlanetOptions {
                 .unwrap_or_else(|| Angle::degrees(45.)),
             distance,
         );
+        let palette = match (&self.palette, &self.preset) {
+            (Some(path), _) => definition::load(path)
+                .with_context(|| format!("error loading palette {:?}", path))?,
+            (None, Some(name)) => Palette::preset(name).with_context(|| {
+                format!(
+                    "unknown preset {:?}, expected one of {}",
+                    name,
+                    Palette::PRESETS.join(", ")
+                )
+            })?,
+            (None, None) => Palette::from_colors(ElevationColor::earthlike()),
+        };
         let mut rng = thread_rng();
-        Planet::new_from_iter(rng.gen(), origin, radius, ElevationColor::earthlike())
+        let mut planet = palette.planet(rng.gen(), origin, radius)?;
+        if self.asteroid {
+            planet.shape = Some(Shape::default());
+            planet.craters = Some(Craters {
+                min_radius: radius * 0.02,
+                max_radius: radius * 0.3,
+                count: 40,
+                ..Craters::default()
+            });
+        }
+        Ok(planet)
     }
 }
+
+#[derive(Debug, StructOpt, PartialEq, Clone)]
+pub struct LoadPlanetOptions {
+    /// A planet definition in a .ron, .toml or .json file
+    pub path: PathBuf,
+}
+
+impl Generate {
+    /// The canvas described by the `width`, `height` and `padding` options. The planet
+    /// is `resolution` pixels wide, or fills the canvas if no resolution was requested.
+    pub fn canvas(&self, resolution: Option<u32>) -> Canvas {
+        let side = resolution.unwrap_or(128) + self.padding * 2;
+        Canvas {
+            width: self.width.unwrap_or(side),
+            height: self.height.unwrap_or(side),
+            diameter: resolution,
+            center: None,
+            padding: self.padding,
+        }
+    }
+
+    /// The quantization requested by the `quantize` and `dither` options
+    pub fn quantize(&self) -> anyhow::Result<Option<Quantize>> {
+        let palette = match self.quantize.as_deref() {
+            Some("pico8") => Quantize::pico8().palette,
+            Some(path) => PaletteFormat::load(Path::new(path))?
+                .into_iter()
+                .map(|entry| entry.color)
+                .collect(),
+            None => return Ok(None),
+        };
+
+        Ok(Some(Quantize {
+            palette,
+            dithering: self.dither,
+        }))
+    }
+}
+
+/// Parses a number of seconds, which must be finite and not negative
+fn parse_period(seconds: &str) -> anyhow::Result<Duration> {
+    let seconds = seconds
+        .parse::<f32>()
+        .with_context(|| format!("invalid period {:?}", seconds))?;
+    Duration::try_from_secs_f32(seconds).map_err(|_| {
+        anyhow::anyhow!(
+            "the period must be a finite number of seconds that isn't negative, not {}",
+            seconds
+        )
+    })
+}
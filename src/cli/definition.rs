@@ -0,0 +1,153 @@
+use crate::{
+    coloring::{DynamicKind, Earthlike},
+    planet::{Light, Planet},
+    quantize::Quantize,
+};
+use anyhow::Context;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+
+/// Everything needed to regenerate a planet: the planet itself, the light shining on it
+/// and the options it is rendered with
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "Kind: Serialize", deserialize = "Kind: Deserialize<'de>"))]
+pub struct PlanetDefinition<Kind> {
+    pub planet: Planet<Kind>,
+
+    /// If present, a shadow is simulated using this light
+    #[serde(default)]
+    pub light: Option<Light>,
+
+    /// Render resolution, in pixels
+    #[serde(default)]
+    pub resolution: Option<u32>,
+
+    /// If present, the rendered image is reduced to a fixed palette
+    #[serde(default)]
+    pub quantize: Option<Quantize>,
+}
+
+impl<Kind> From<Planet<Kind>> for PlanetDefinition<Kind> {
+    fn from(planet: Planet<Kind>) -> Self {
+        Self {
+            planet,
+            light: None,
+            resolution: None,
+            quantize: None,
+        }
+    }
+}
+
+/// The file formats definitions and palettes can be stored in, chosen by the file's extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Ron,
+    Toml,
+    Json,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> anyhow::Result<Self> {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("ron") => Ok(Format::Ron),
+            Some("toml") => Ok(Format::Toml),
+            Some("json") => Ok(Format::Json),
+            _ => anyhow::bail!(
+                "unknown file format for {:?}, expected a .ron, .toml or .json file",
+                path
+            ),
+        }
+    }
+}
+
+impl From<PlanetDefinition<Earthlike>> for PlanetDefinition<DynamicKind> {
+    fn from(definition: PlanetDefinition<Earthlike>) -> Self {
+        Self {
+            planet: definition.planet.map_kinds(&DynamicKind::from_kind),
+            light: definition.light,
+            resolution: definition.resolution,
+            quantize: definition.quantize,
+        }
+    }
+}
+
+impl PlanetDefinition<DynamicKind> {
+    /// Reads a definition from `path`, using the format matching its extension.
+    /// Definitions saved before palettes were added store `Earthlike` kinds by name, and
+    /// are converted to `DynamicKind`s.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        load::<Self>(path)
+            .or_else(|error| {
+                load::<PlanetDefinition<Earthlike>>(path)
+                    .map(PlanetDefinition::from)
+                    .map_err(|_| error)
+            })
+            .with_context(|| format!("error loading planet definition {:?}", path))
+    }
+}
+
+impl<Kind> PlanetDefinition<Kind>
+where
+    Kind: Serialize + DeserializeOwned,
+{
+    /// Writes this definition to `path`, using the format matching its extension
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        save(self, path).with_context(|| format!("error saving planet definition {:?}", path))
+    }
+}
+
+/// Reads a value from `path`, using the format matching its extension
+pub fn load<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let format = Format::from_path(path)?;
+    let contents = std::fs::read_to_string(path)?;
+    let value = match format {
+        Format::Ron => ron::from_str(&contents)?,
+        // Read through JSON, for the enum variants `save` stores as tables
+        Format::Toml => {
+            let toml = toml::from_str::<toml::Value>(&contents)?;
+            serde_json::from_value(serde_json::to_value(toml)?)?
+        }
+        Format::Json => serde_json::from_str(&contents)?,
+    };
+
+    Ok(value)
+}
+
+/// Writes `value` to `path`, using the format matching its extension
+pub fn save<T: Serialize>(value: &T, path: &Path) -> anyhow::Result<()> {
+    let contents = match Format::from_path(path)? {
+        Format::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?,
+        // TOML can't store enum variants with data directly, so the value is converted
+        // to JSON first, which stores them as single-entry tables. Converting to a
+        // `toml::Value` also puts plain values before tables, as TOML requires.
+        Format::Toml => {
+            let json = without_nulls(serde_json::to_value(value)?);
+            toml::to_string_pretty(&toml::Value::try_from(json)?)?
+        }
+        Format::Json => serde_json::to_string_pretty(value)?,
+    };
+
+    Ok(std::fs::write(path, contents)?)
+}
+
+/// Removes null fields, which TOML can't store. Every optional field defaults to `None`
+/// when it is missing.
+fn without_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+        ),
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(without_nulls).collect())
+        }
+        value => value,
+    }
+}
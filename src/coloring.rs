@@ -1,9 +1,25 @@
-use crate::{planet::SurfaceDefinition, types::Kilometers};
+use crate::{planet::SurfaceDefinition, rivers::Rivers, types::Kilometers};
 use euclid::Length;
 use palette::Srgb;
 
+mod dynamic;
+mod formats;
+mod harmony;
+mod presets;
+
+pub use dynamic::{DynamicKind, Palette, PaletteColor};
+pub use formats::{PaletteEntry, PaletteFormat};
+pub use harmony::Harmony;
+pub use presets::{
+    Europalike, Iolike, Junglelike, Jupiterlike, Marslike, Moonlike, Oceanlike, Venuslike,
+};
+
 /// A pairing of an elevation and a color
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct ElevationColor<Kind> {
     pub kind: Kind,
 
@@ -39,6 +55,10 @@ impl<Kind> ElevationColor<Kind> {
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Earthlike {
     DeepOcean,
     ShallowOcean,
@@ -47,6 +67,7 @@ pub enum Earthlike {
     Forest,
     Mountain,
     Snow,
+    River,
 }
 
 impl ElevationColor<Earthlike> {
@@ -64,13 +85,28 @@ impl ElevationColor<Earthlike> {
     }
 }
 
+impl Rivers<Earthlike> {
+    /// Rivers colored like the shallow ocean of `ElevationColor::earthlike()`
+    pub fn earthlike() -> Self {
+        Rivers::from_u8(Earthlike::River, 98, 125, 223)
+    }
+}
+
 impl SurfaceDefinition for Earthlike {
     fn max_chaos() -> f32 {
         7.
     }
+
+    fn is_liquid(&self) -> bool {
+        matches!(self, Earthlike::DeepOcean | Earthlike::ShallowOcean)
+    }
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Sunlike {
     DeepBase,
     BrightMiddle,
@@ -0,0 +1,214 @@
+use crate::{
+    banding::Banding,
+    coloring::ElevationColor,
+    planet::{Planet, SurfaceDefinition},
+    types::Kilometers,
+};
+use euclid::{Length, Point2D};
+use palette::Srgb;
+use std::{
+    collections::BTreeSet,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// Every kind name created so far. Kinds with the same name share one allocation.
+static NAMES: Mutex<BTreeSet<Arc<str>>> = Mutex::new(BTreeSet::new());
+
+/// A kind of surface that is defined at runtime, such as from a palette file, rather than
+/// by an enum. Names are interned, which makes cloning and comparing kinds cheap.
+#[derive(Clone, Hash, Eq, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "KindDefinition", into = "KindDefinition")
+)]
+pub struct DynamicKind {
+    name: Arc<str>,
+    liquid: bool,
+}
+
+impl DynamicKind {
+    /// Creates a kind named `name`. Rivers flow until they reach a `liquid` kind.
+    pub fn new(name: &str, liquid: bool) -> Self {
+        let mut names = NAMES.lock().unwrap();
+        let name = match names.get(name) {
+            Some(existing) => existing.clone(),
+            None => {
+                let interned = Arc::<str>::from(name);
+                names.insert(interned.clone());
+                interned
+            }
+        };
+
+        Self { name, liquid }
+    }
+
+    /// Creates a kind with the same name and liquidity as a compile-time `kind`
+    pub fn from_kind<Kind: SurfaceDefinition + fmt::Debug>(kind: &Kind) -> Self {
+        Self::new(&kind.kind_name(), kind.is_liquid())
+    }
+
+    /// The name of this kind
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl SurfaceDefinition for DynamicKind {
+    fn is_liquid(&self) -> bool {
+        self.liquid
+    }
+
+    fn kind_name(&self) -> String {
+        self.name.to_string()
+    }
+}
+
+impl fmt::Debug for DynamicKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.name, f)
+    }
+}
+
+impl fmt::Display for DynamicKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// How a `DynamicKind` is stored
+#[cfg(feature = "serialization")]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct KindDefinition {
+    name: String,
+    #[serde(default)]
+    liquid: bool,
+}
+
+#[cfg(feature = "serialization")]
+impl From<KindDefinition> for DynamicKind {
+    fn from(definition: KindDefinition) -> Self {
+        Self::new(&definition.name, definition.liquid)
+    }
+}
+
+#[cfg(feature = "serialization")]
+impl From<DynamicKind> for KindDefinition {
+    fn from(kind: DynamicKind) -> Self {
+        Self {
+            name: kind.name.to_string(),
+            liquid: kind.liquid,
+        }
+    }
+}
+
+/// A palette of runtime-defined kinds, meant to be loaded from a data file
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Palette {
+    /// The `max_chaos` of planets created with this palette
+    #[cfg_attr(feature = "serialization", serde(default = "DynamicKind::max_chaos"))]
+    pub max_chaos: f32,
+
+    /// The kinds of surface in this palette
+    pub colors: Vec<PaletteColor>,
+
+    /// The banding of planets created with this palette
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub banding: Option<Banding>,
+}
+
+/// A kind of surface in a `Palette`
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct PaletteColor {
+    /// The name of the kind, such as "Crystal Plains"
+    pub kind: String,
+
+    /// Whether the kind is a liquid, which rivers flow into
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub liquid: bool,
+
+    /// The color as a hexadecimal code, e.g., "#627DDF"
+    pub color: String,
+
+    /// The elevation of this color
+    pub elevation: Length<f32, Kilometers>,
+}
+
+impl Palette {
+    /// Creates a palette from compile-time `colors`, naming each kind by its `kind_name()`
+    pub fn from_colors<Kind, I>(colors: I) -> Self
+    where
+        Kind: SurfaceDefinition + fmt::Debug,
+        I: IntoIterator<Item = ElevationColor<Kind>>,
+    {
+        Self {
+            max_chaos: Kind::max_chaos(),
+            colors: colors
+                .into_iter()
+                .map(|color| PaletteColor {
+                    kind: color.kind.kind_name(),
+                    liquid: color.kind.is_liquid(),
+                    color: to_hex(color.color.into_format()),
+                    elevation: color.elevation,
+                })
+                .collect(),
+            banding: None,
+        }
+    }
+
+    /// The elevation colors of this palette. Returns an error if a color isn't a valid
+    /// hexadecimal code.
+    pub fn colors(&self) -> anyhow::Result<Vec<ElevationColor<DynamicKind>>> {
+        self.colors
+            .iter()
+            .map(|color| {
+                let (r, g, b) = parse_hex(&color.color)?;
+                Ok(ElevationColor::from_u8(
+                    DynamicKind::new(&color.kind, color.liquid),
+                    r,
+                    g,
+                    b,
+                    color.elevation,
+                ))
+            })
+            .collect()
+    }
+
+    /// Creates a planet colored by this palette
+    pub fn planet(
+        &self,
+        seed: u64,
+        origin: Point2D<f32, Kilometers>,
+        radius: Length<f32, Kilometers>,
+    ) -> anyhow::Result<Planet<DynamicKind>> {
+        let mut planet =
+            Planet::new_from_iter_with_chaos(seed, origin, radius, self.colors()?, self.max_chaos);
+        planet.banding = self.banding;
+        Ok(planet)
+    }
+}
+
+/// Formats `color` as a hexadecimal code, e.g., "#627ddf"
+pub(crate) fn to_hex(color: Srgb<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+}
+
+fn parse_hex(color: &str) -> anyhow::Result<(u8, u8, u8)> {
+    let hex = color.trim().trim_start_matches('#');
+    anyhow::ensure!(
+        hex.len() == 6 && hex.is_ascii(),
+        "Only 6-character hexadecimal codes are allowed, e.g., FF1234, not {:?}",
+        color
+    );
+    let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+    Ok((component(0)?, component(2)?, component(4)?))
+}
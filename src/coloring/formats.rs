@@ -0,0 +1,294 @@
+use crate::{
+    coloring::{dynamic::to_hex, DynamicKind, ElevationColor, Palette, PaletteColor},
+    planet::SurfaceDefinition,
+    types::Kilometers,
+};
+use anyhow::Context;
+use euclid::Length;
+use palette::Srgb;
+use std::{fmt::Write, path::Path};
+
+/// A file format used by pixel art tools to share palettes
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PaletteFormat {
+    /// GIMP palettes (.gpl), which Aseprite and Lospec also use
+    Gimp,
+    /// Lospec's list of hexadecimal codes (.hex)
+    Hex,
+    /// JASC palettes (.pal), used by Paint Shop Pro and Aseprite
+    Jasc,
+}
+
+/// A color read from or written to a palette file
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaletteEntry {
+    pub color: Srgb<u8>,
+
+    /// The name the file gives the color. Only GIMP palettes name their colors.
+    pub name: Option<String>,
+}
+
+impl PaletteFormat {
+    /// Chooses the format matching the extension of `path`
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("gpl") => Ok(PaletteFormat::Gimp),
+            Some("hex") => Ok(PaletteFormat::Hex),
+            Some("pal") => Ok(PaletteFormat::Jasc),
+            _ => anyhow::bail!(
+                "unknown palette format for {:?}, expected a .gpl, .hex or .pal file",
+                path
+            ),
+        }
+    }
+
+    /// Parses the colors out of a palette file's `contents`
+    pub fn parse(self, contents: &str) -> anyhow::Result<Vec<PaletteEntry>> {
+        let mut lines = contents
+            .lines()
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty());
+        match self {
+            PaletteFormat::Gimp => {
+                match lines.next() {
+                    Some((_, "GIMP Palette")) => {}
+                    _ => anyhow::bail!("missing \"GIMP Palette\" header"),
+                }
+                lines
+                    .filter(|(_, line)| {
+                        !line.starts_with('#')
+                            && !line.starts_with("Name:")
+                            && !line.starts_with("Columns:")
+                    })
+                    .map(|(index, line)| {
+                        let mut parts = line.split_whitespace();
+                        let color = parse_components(&mut parts)
+                            .with_context(|| format!("invalid color on line {}", index + 1))?;
+                        let name = parts.collect::<Vec<_>>().join(" ");
+                        Ok(PaletteEntry {
+                            color,
+                            name: if name.is_empty() { None } else { Some(name) },
+                        })
+                    })
+                    .collect()
+            }
+            PaletteFormat::Hex => lines
+                .map(|(index, line)| {
+                    let hex = line.trim_start_matches('#');
+                    anyhow::ensure!(
+                        hex.len() == 6 && hex.is_ascii(),
+                        "invalid color on line {}",
+                        index + 1
+                    );
+                    let component = |start: usize| {
+                        u8::from_str_radix(&hex[start..start + 2], 16)
+                            .with_context(|| format!("invalid color on line {}", index + 1))
+                    };
+                    Ok(PaletteEntry {
+                        color: Srgb::new(component(0)?, component(2)?, component(4)?),
+                        name: None,
+                    })
+                })
+                .collect(),
+            PaletteFormat::Jasc => {
+                match (lines.next(), lines.next()) {
+                    (Some((_, "JASC-PAL")), Some((_, "0100"))) => {}
+                    _ => anyhow::bail!("missing \"JASC-PAL\" header"),
+                }
+                let count = match lines.next() {
+                    Some((_, count)) => count.parse::<usize>().context("invalid color count")?,
+                    None => anyhow::bail!("missing color count"),
+                };
+                let entries = lines
+                    .map(|(index, line)| {
+                        // Some tools write a fourth alpha component, which is ignored
+                        let color = parse_components(&mut line.split_whitespace())
+                            .with_context(|| format!("invalid color on line {}", index + 1))?;
+                        Ok(PaletteEntry { color, name: None })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                anyhow::ensure!(
+                    entries.len() == count,
+                    "expected {} colors, found {}",
+                    count,
+                    entries.len()
+                );
+                Ok(entries)
+            }
+        }
+    }
+
+    /// Writes `entries` in this format. `name` is the palette's name, which only GIMP
+    /// palettes store.
+    pub fn write(self, name: &str, entries: &[PaletteEntry]) -> String {
+        let mut contents = String::new();
+        match self {
+            PaletteFormat::Gimp => {
+                writeln!(contents, "GIMP Palette").unwrap();
+                writeln!(contents, "Name: {}", name).unwrap();
+                writeln!(contents, "#").unwrap();
+                for entry in entries {
+                    writeln!(
+                        contents,
+                        "{:3} {:3} {:3}\t{}",
+                        entry.color.red,
+                        entry.color.green,
+                        entry.color.blue,
+                        entry.name.as_deref().unwrap_or_default()
+                    )
+                    .unwrap();
+                }
+            }
+            PaletteFormat::Hex => {
+                for entry in entries {
+                    writeln!(
+                        contents,
+                        "{:02x}{:02x}{:02x}",
+                        entry.color.red, entry.color.green, entry.color.blue
+                    )
+                    .unwrap();
+                }
+            }
+            PaletteFormat::Jasc => {
+                // JASC palettes use Windows line endings
+                write!(contents, "JASC-PAL\r\n0100\r\n{}\r\n", entries.len()).unwrap();
+                for entry in entries {
+                    write!(
+                        contents,
+                        "{} {} {}\r\n",
+                        entry.color.red, entry.color.green, entry.color.blue
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        contents
+    }
+
+    /// Reads the colors of the palette file at `path`, using the format matching its extension
+    pub fn load(path: &Path) -> anyhow::Result<Vec<PaletteEntry>> {
+        let format = Self::from_path(path)?;
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("error reading palette {:?}", path))?;
+        format
+            .parse(&contents)
+            .with_context(|| format!("error parsing palette {:?}", path))
+    }
+
+    /// Writes `entries` to `path`, using the format matching its extension
+    pub fn save(path: &Path, entries: &[PaletteEntry]) -> anyhow::Result<()> {
+        let format = Self::from_path(path)?;
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        std::fs::write(path, format.write(name, entries))
+            .with_context(|| format!("error writing palette {:?}", path))
+    }
+}
+
+/// Parses three decimal color components, e.g., "98 125 223"
+fn parse_components<'a, I: Iterator<Item = &'a str>>(parts: &mut I) -> anyhow::Result<Srgb<u8>> {
+    let mut component = || -> anyhow::Result<u8> {
+        Ok(parts
+            .next()
+            .context("expected three color components")?
+            .parse()?)
+    };
+    Ok(Srgb::new(component()?, component()?, component()?))
+}
+
+impl<Kind> ElevationColor<Kind> {
+    /// Reads the palette file at `path`, pairing its colors in order with `kinds` and
+    /// their elevations. The file must contain exactly one color for each kind.
+    pub fn load_palette<I>(path: &Path, kinds: I) -> anyhow::Result<Vec<Self>>
+    where
+        I: IntoIterator<Item = (Kind, Length<f32, Kilometers>)>,
+    {
+        let entries = PaletteFormat::load(path)?;
+        let kinds = kinds.into_iter().collect::<Vec<_>>();
+        anyhow::ensure!(
+            entries.len() == kinds.len(),
+            "{:?} contains {} colors, but {} kinds were provided",
+            path,
+            entries.len(),
+            kinds.len()
+        );
+
+        Ok(entries
+            .into_iter()
+            .zip(kinds)
+            .map(|(entry, (kind, elevation))| {
+                let color = entry.color;
+                ElevationColor::from_u8(kind, color.red, color.green, color.blue, elevation)
+            })
+            .collect())
+    }
+
+    /// Writes `colors` to the palette file at `path`, naming each color after its kind
+    pub fn save_palette<'a, I>(colors: I, path: &Path) -> anyhow::Result<()>
+    where
+        Kind: SurfaceDefinition + std::fmt::Debug + 'a,
+        I: IntoIterator<Item = &'a Self>,
+    {
+        let entries = colors
+            .into_iter()
+            .map(|color| PaletteEntry {
+                color: color.color.into_format(),
+                name: Some(color.kind.kind_name()),
+            })
+            .collect::<Vec<_>>();
+        PaletteFormat::save(path, &entries)
+    }
+}
+
+impl Palette {
+    /// Reads the palette file at `path`, pairing its colors in order with `elevations`.
+    /// Kinds are named after the file's color names, or numbered if it has none. No kind
+    /// is a liquid until its `liquid` is set.
+    pub fn import(path: &Path, elevations: &[Length<f32, Kilometers>]) -> anyhow::Result<Self> {
+        let entries = PaletteFormat::load(path)?;
+        anyhow::ensure!(
+            entries.len() == elevations.len(),
+            "{:?} contains {} colors, but {} elevations were provided",
+            path,
+            entries.len(),
+            elevations.len()
+        );
+
+        Ok(Self {
+            max_chaos: DynamicKind::max_chaos(),
+            colors: entries
+                .into_iter()
+                .zip(elevations)
+                .enumerate()
+                .map(|(index, (entry, elevation))| PaletteColor {
+                    kind: entry.name.unwrap_or_else(|| format!("Color {}", index + 1)),
+                    liquid: false,
+                    color: to_hex(entry.color),
+                    elevation: *elevation,
+                })
+                .collect(),
+            banding: None,
+        })
+    }
+
+    /// Writes the colors of this palette to the palette file at `path`
+    pub fn export(&self, path: &Path) -> anyhow::Result<()> {
+        let colors = self.colors()?;
+        let entries = colors
+            .iter()
+            .map(|color| PaletteEntry {
+                color: color.color.into_format(),
+                name: Some(color.kind.name().to_string()),
+            })
+            .collect::<Vec<_>>();
+        PaletteFormat::save(path, &entries)
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    coloring::{DynamicKind, ElevationColor, Palette},
+    planet::SurfaceDefinition,
+    types::Kilometers,
+};
+use euclid::Length;
+use palette::{Lch, Limited, LinSrgb, Srgb};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Rules for generating palettes from color theory. Land and liquid kinds each get a ramp
+/// of colors in LCh space, which step evenly in lightness while drifting in hue. The
+/// liquid's hue sits across the color wheel from the land's.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Harmony {
+    /// How far the liquid's hue can stray from the complement of the land's hue, in degrees
+    pub complement_spread: f32,
+
+    /// The most a ramp's hue drifts between its lowest and highest color, in degrees
+    pub hue_shift: f32,
+
+    /// The chroma of the most saturated color in a ramp
+    pub chroma: f32,
+
+    /// The smallest difference in lightness between neighboring colors in a ramp. Larger
+    /// steps keep kinds distinguishable at small sizes.
+    pub lightness_step: f32,
+}
+
+impl Default for Harmony {
+    fn default() -> Self {
+        Self {
+            complement_spread: 30.,
+            hue_shift: 60.,
+            chroma: 55.,
+            lightness_step: 10.,
+        }
+    }
+}
+
+impl Harmony {
+    /// Generates a color for each of `kinds` at its elevation. The same `seed` always
+    /// generates the same colors.
+    pub fn generate<Kind, I>(&self, seed: u64, kinds: I) -> Vec<ElevationColor<Kind>>
+    where
+        Kind: SurfaceDefinition,
+        I: IntoIterator<Item = (Kind, Length<f32, Kilometers>)>,
+    {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let land_hue = rng.gen_range(0f32..360.);
+        let liquid_hue =
+            land_hue + 180. + rng.gen_range(-1f32..1.) * self.complement_spread.max(0.);
+
+        let mut kinds = kinds.into_iter().collect::<Vec<_>>();
+        kinds.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        let (liquids, land): (Vec<_>, Vec<_>) =
+            kinds.into_iter().partition(|(kind, _)| kind.is_liquid());
+
+        // Liquids stay darker than the land they surround
+        let mut colors = self.ramp(&mut rng, liquid_hue, 15., 60., liquids);
+        colors.extend(self.ramp(&mut rng, land_hue, 30., 95., land));
+        colors
+    }
+
+    /// Colors `kinds` in order of elevation with lightnesses between `darkest` and `lightest`
+    fn ramp<Kind, R: Rng>(
+        &self,
+        rng: &mut R,
+        hue: f32,
+        darkest: f32,
+        lightest: f32,
+        kinds: Vec<(Kind, Length<f32, Kilometers>)>,
+    ) -> Vec<ElevationColor<Kind>> {
+        let steps = kinds.len().saturating_sub(1) as f32;
+        let min_span = (self.lightness_step * steps).min(lightest - darkest);
+        let span = rng.gen_range(min_span..=(lightest - darkest).max(min_span));
+        let start = rng.gen_range(darkest..=lightest - span);
+        // Lighter colors drift one way around the color wheel, and darker colors the other
+        let hue_shift = self.hue_shift * if rng.gen_bool(0.5) { 1. } else { -1. };
+        let chroma = self.chroma * rng.gen_range(0.6f32..1.);
+
+        kinds
+            .into_iter()
+            .enumerate()
+            .map(|(index, (kind, elevation))| {
+                let t = if steps > 0. {
+                    index as f32 / steps
+                } else {
+                    0.5
+                };
+                // The ends of a ramp are less saturated, like shadows and highlights
+                let saturation = 1. - 0.5 * (2. * t - 1.).powi(2);
+                let color = Lch::new(
+                    start + span * t,
+                    chroma * saturation,
+                    hue + hue_shift * (t - 0.5),
+                );
+                ElevationColor {
+                    kind,
+                    color: to_srgb(color),
+                    elevation,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Converts `color` to sRGB, reducing its chroma until it fits in the sRGB gamut
+fn to_srgb(mut color: Lch) -> Srgb<f32> {
+    loop {
+        let rgb = LinSrgb::from(color);
+        if rgb.is_valid() || color.chroma < 1. {
+            return Srgb::from_linear(rgb.clamp());
+        }
+        color.chroma *= 0.9;
+    }
+}
+
+impl Palette {
+    /// Generates a palette for an alien world using `Harmony::default()`, with two liquid
+    /// kinds and four kinds of land
+    pub fn random(seed: u64) -> Self {
+        let kinds = [
+            ("Deep Liquid", true, -2000.),
+            ("Shallow Liquid", true, 0.),
+            ("Shore", false, 100.),
+            ("Lowland", false, 300.),
+            ("Highland", false, 900.),
+            ("Peak", false, 1600.),
+        ];
+        let colors = Harmony::default().generate(
+            seed,
+            kinds.iter().map(|&(name, liquid, elevation)| {
+                (DynamicKind::new(name, liquid), Kilometers::new(elevation))
+            }),
+        );
+
+        Self {
+            max_chaos: 6.,
+            ..Palette::from_colors(colors)
+        }
+    }
+}
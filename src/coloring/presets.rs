@@ -0,0 +1,336 @@
+use crate::{
+    banding::Banding,
+    coloring::{ElevationColor, Palette},
+    planet::SurfaceDefinition,
+    types::Kilometers,
+};
+
+/// A dusty, dry world like Mars
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Marslike {
+    Basin,
+    Lowland,
+    Dunes,
+    Plateau,
+    Highland,
+    PolarCap,
+}
+
+impl ElevationColor<Marslike> {
+    /// A palette of rusty deserts that kinda resembles Mars
+    pub fn marslike() -> Vec<Self> {
+        vec![
+            ElevationColor::from_u8(Marslike::Basin, 112, 52, 38, Kilometers::new(-1500.)),
+            ElevationColor::from_u8(Marslike::Lowland, 166, 80, 48, Kilometers::new(-200.)),
+            ElevationColor::from_u8(Marslike::Dunes, 206, 122, 70, Kilometers::new(200.)),
+            ElevationColor::from_u8(Marslike::Plateau, 156, 94, 64, Kilometers::new(700.)),
+            ElevationColor::from_u8(Marslike::Highland, 114, 72, 56, Kilometers::new(1300.)),
+            ElevationColor::from_u8(Marslike::PolarCap, 240, 234, 226, Kilometers::new(1800.)),
+        ]
+    }
+}
+
+impl SurfaceDefinition for Marslike {
+    fn max_chaos() -> f32 {
+        6.
+    }
+}
+
+/// A frozen world crossed by cracks, like Europa
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Europalike {
+    Fissure,
+    DirtyIce,
+    Ice,
+    Frost,
+}
+
+impl ElevationColor<Europalike> {
+    /// A palette of ice with rust-colored cracks that kinda resembles Europa
+    pub fn europalike() -> Vec<Self> {
+        vec![
+            ElevationColor::from_u8(Europalike::Fissure, 134, 84, 62, Kilometers::new(-600.)),
+            ElevationColor::from_u8(Europalike::DirtyIce, 190, 168, 148, Kilometers::new(-250.)),
+            ElevationColor::from_u8(Europalike::Ice, 210, 224, 234, Kilometers::new(0.)),
+            ElevationColor::from_u8(Europalike::Frost, 240, 246, 252, Kilometers::new(400.)),
+        ]
+    }
+}
+
+impl SurfaceDefinition for Europalike {
+    fn max_chaos() -> f32 {
+        9.
+    }
+}
+
+/// A volcanic world covered in sulfur, like Io
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Iolike {
+    LavaLake,
+    CooledLava,
+    Sulfur,
+    Frost,
+}
+
+impl ElevationColor<Iolike> {
+    /// A palette of lava and sulfur that kinda resembles Io
+    pub fn iolike() -> Vec<Self> {
+        vec![
+            ElevationColor::from_u8(Iolike::LavaLake, 255, 96, 24, Kilometers::new(-500.)),
+            ElevationColor::from_u8(Iolike::CooledLava, 64, 42, 32, Kilometers::new(-300.)),
+            ElevationColor::from_u8(Iolike::Sulfur, 226, 204, 84, Kilometers::new(0.)),
+            ElevationColor::from_u8(Iolike::Frost, 244, 238, 198, Kilometers::new(500.)),
+        ]
+    }
+}
+
+impl SurfaceDefinition for Iolike {
+    fn max_chaos() -> f32 {
+        8.
+    }
+
+    fn is_liquid(&self) -> bool {
+        matches!(self, Iolike::LavaLake)
+    }
+}
+
+/// A world almost entirely covered by water
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Oceanlike {
+    Abyss,
+    DeepOcean,
+    ShallowOcean,
+    Reef,
+    Island,
+    Peak,
+}
+
+impl ElevationColor<Oceanlike> {
+    /// A palette of deep water dotted with small islands
+    pub fn oceanlike() -> Vec<Self> {
+        vec![
+            ElevationColor::from_u8(Oceanlike::Abyss, 8, 20, 92, Kilometers::new(-3000.)),
+            ElevationColor::from_u8(Oceanlike::DeepOcean, 22, 60, 160, Kilometers::new(-1800.)),
+            ElevationColor::from_u8(
+                Oceanlike::ShallowOcean,
+                60,
+                136,
+                210,
+                Kilometers::new(-700.),
+            ),
+            ElevationColor::from_u8(Oceanlike::Reef, 84, 196, 190, Kilometers::new(-100.)),
+            ElevationColor::from_u8(Oceanlike::Island, 212, 200, 142, Kilometers::new(0.)),
+            ElevationColor::from_u8(Oceanlike::Peak, 88, 140, 70, Kilometers::new(1200.)),
+        ]
+    }
+}
+
+impl SurfaceDefinition for Oceanlike {
+    fn max_chaos() -> f32 {
+        5.
+    }
+
+    fn is_liquid(&self) -> bool {
+        matches!(
+            self,
+            Oceanlike::Abyss | Oceanlike::DeepOcean | Oceanlike::ShallowOcean | Oceanlike::Reef
+        )
+    }
+}
+
+/// A world hidden beneath thick, toxic clouds, like Venus
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Venuslike {
+    LowerCloud,
+    Haze,
+    UpperCloud,
+    SulfuricVeil,
+}
+
+impl ElevationColor<Venuslike> {
+    /// A palette of sulfuric clouds that kinda resembles Venus
+    pub fn venuslike() -> Vec<Self> {
+        vec![
+            ElevationColor::from_u8(Venuslike::LowerCloud, 172, 128, 62, Kilometers::new(-500.)),
+            ElevationColor::from_u8(Venuslike::Haze, 208, 168, 92, Kilometers::new(0.)),
+            ElevationColor::from_u8(Venuslike::UpperCloud, 232, 206, 140, Kilometers::new(400.)),
+            ElevationColor::from_u8(
+                Venuslike::SulfuricVeil,
+                248,
+                238,
+                198,
+                Kilometers::new(800.),
+            ),
+        ]
+    }
+}
+
+impl SurfaceDefinition for Venuslike {
+    fn max_chaos() -> f32 {
+        3.
+    }
+}
+
+/// A gray, airless world like Earth's moon. Pairs well with `Craters`.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Moonlike {
+    Mare,
+    Plains,
+    Highland,
+    Peak,
+}
+
+impl ElevationColor<Moonlike> {
+    /// A palette of gray dust that kinda resembles Earth's moon
+    pub fn moonlike() -> Vec<Self> {
+        vec![
+            ElevationColor::from_u8(Moonlike::Mare, 78, 78, 84, Kilometers::new(-500.)),
+            ElevationColor::from_u8(Moonlike::Plains, 128, 128, 128, Kilometers::new(0.)),
+            ElevationColor::from_u8(Moonlike::Highland, 168, 168, 162, Kilometers::new(500.)),
+            ElevationColor::from_u8(Moonlike::Peak, 208, 208, 202, Kilometers::new(1200.)),
+        ]
+    }
+}
+
+impl SurfaceDefinition for Moonlike {
+    fn max_chaos() -> f32 {
+        10.
+    }
+}
+
+/// A hot, wet world covered in dense vegetation
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Junglelike {
+    DeepWater,
+    Swamp,
+    Wetland,
+    Jungle,
+    DenseJungle,
+    Highland,
+}
+
+impl ElevationColor<Junglelike> {
+    /// A palette of swamps and rainforest
+    pub fn junglelike() -> Vec<Self> {
+        vec![
+            ElevationColor::from_u8(Junglelike::DeepWater, 22, 62, 72, Kilometers::new(-1500.)),
+            ElevationColor::from_u8(Junglelike::Swamp, 58, 94, 62, Kilometers::new(0.)),
+            ElevationColor::from_u8(Junglelike::Wetland, 78, 120, 48, Kilometers::new(100.)),
+            ElevationColor::from_u8(Junglelike::Jungle, 34, 96, 34, Kilometers::new(400.)),
+            ElevationColor::from_u8(Junglelike::DenseJungle, 16, 62, 26, Kilometers::new(900.)),
+            ElevationColor::from_u8(Junglelike::Highland, 92, 112, 70, Kilometers::new(1500.)),
+        ]
+    }
+}
+
+impl SurfaceDefinition for Junglelike {
+    fn max_chaos() -> f32 {
+        7.
+    }
+
+    fn is_liquid(&self) -> bool {
+        matches!(self, Junglelike::DeepWater | Junglelike::Swamp)
+    }
+}
+
+/// A gas giant with clouds of ammonia, like Jupiter
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Jupiterlike {
+    Storm,
+    Belt,
+    Zone,
+    AmmoniaCloud,
+}
+
+impl ElevationColor<Jupiterlike> {
+    /// A palette of banded ammonia clouds that kinda resembles Jupiter. The bands come from
+    /// setting the planet's `banding`.
+    pub fn jupiterlike() -> Vec<Self> {
+        vec![
+            ElevationColor::from_u8(Jupiterlike::Storm, 186, 92, 58, Kilometers::new(-700.)),
+            ElevationColor::from_u8(Jupiterlike::Belt, 160, 112, 78, Kilometers::new(-300.)),
+            ElevationColor::from_u8(Jupiterlike::Zone, 224, 200, 160, Kilometers::new(100.)),
+            ElevationColor::from_u8(
+                Jupiterlike::AmmoniaCloud,
+                244,
+                238,
+                222,
+                Kilometers::new(500.),
+            ),
+        ]
+    }
+}
+
+impl SurfaceDefinition for Jupiterlike {
+    fn max_chaos() -> f32 {
+        2.
+    }
+}
+
+impl Palette {
+    /// The names of the presets available from `Palette::preset`
+    pub const PRESETS: &'static [&'static str] = &[
+        "earthlike",
+        "mars",
+        "europa",
+        "io",
+        "ocean",
+        "venus",
+        "moon",
+        "jungle",
+        "jupiter",
+    ];
+
+    /// Returns the palette of the preset named `name`, which is one of `Palette::PRESETS`
+    /// or the kind of world it describes: desert, ice, lava, toxic, barren or gas-giant.
+    pub fn preset(name: &str) -> Option<Self> {
+        let palette = match name.to_ascii_lowercase().as_str() {
+            "earthlike" | "earth" => Palette::from_colors(ElevationColor::earthlike()),
+            "mars" | "desert" => Palette::from_colors(ElevationColor::marslike()),
+            "europa" | "ice" => Palette::from_colors(ElevationColor::europalike()),
+            "io" | "lava" => Palette::from_colors(ElevationColor::iolike()),
+            "ocean" => Palette::from_colors(ElevationColor::oceanlike()),
+            "venus" | "toxic" => Palette::from_colors(ElevationColor::venuslike()),
+            "moon" | "barren" => Palette::from_colors(ElevationColor::moonlike()),
+            "jungle" => Palette::from_colors(ElevationColor::junglelike()),
+            "jupiter" | "gas-giant" => Palette {
+                banding: Some(Banding::default()),
+                ..Palette::from_colors(ElevationColor::jupiterlike())
+            },
+            _ => return None,
+        };
+        Some(palette)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{coloring::ElevationColor, planet::GenerationAlgorithm, types::Kilometers};
+use euclid::Length;
+
+/// A target for how much of a planet's surface is covered by a kind of terrain
+/// and every kind below it. For example, a target of 70% for the shallowest ocean
+/// produces a planet that is 70% ocean.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Coverage<Kind> {
+    /// The highest kind of terrain included in the target
+    pub kind: Kind,
+
+    /// The fraction of the surface to cover, from 0.0 to 1.0
+    pub fraction: f32,
+}
+
+/// How many points on the sphere are sampled when solving for a target
+pub(crate) const SAMPLES: u32 = 4096;
+
+/// How many times the search for an offset halves its range
+const SOLVER_ITERATIONS: u32 = 32;
+
+impl<Kind: PartialEq> Coverage<Kind> {
+    pub fn new(kind: Kind, fraction: f32) -> Self {
+        Self { kind, fraction }
+    }
+
+    /// Returns the offset to add to every elevation so that `fraction` of `samples` are
+    /// colored by `kind` or a lower ElevationColor. `samples` must be sorted. Returns
+    /// None if `kind` isn't in `elevations`.
+    pub(crate) fn solve_offset(
+        &self,
+        elevations: &[ElevationColor<Kind>],
+        samples: &[Length<f32, Kilometers>],
+        algorithm: GenerationAlgorithm,
+    ) -> Option<Length<f32, Kilometers>> {
+        let index = elevations
+            .iter()
+            .position(|elevation| elevation.kind == self.kind)?;
+        if samples.is_empty() {
+            return None;
+        } else if index + 1 == elevations.len() {
+            // Every elevation is colored by the highest kind or one below it
+            return Some(Length::default());
+        }
+
+        // The stretches of elevation colored by the kind or a lower one, as `(bottom, top)`.
+        // Everything below the lowest ElevationColor is colored by it.
+        let lowest = elevations[0].elevation;
+        let mut covered = vec![(Length::new(f32::NEG_INFINITY), lowest)];
+        let mut bottom = lowest;
+        for (top, band_index) in bands(elevations, algorithm) {
+            if band_index <= index {
+                match covered.last_mut() {
+                    Some(last) if last.1 == bottom => last.1 = top,
+                    _ => covered.push((bottom, top)),
+                }
+            }
+            bottom = top;
+        }
+
+        if let [(_, top)] = covered.as_slice() {
+            return Some(*top - quantile(samples, self.fraction));
+        }
+
+        // When the kind's bands aren't contiguous, search for the offset instead. Raising
+        // the surface leaves less of it covered.
+        let covered_fraction = |offset: Length<f32, Kilometers>| {
+            let count = samples
+                .iter()
+                .filter(|sample| {
+                    let elevation = **sample + offset;
+                    covered
+                        .iter()
+                        .any(|(bottom, top)| elevation >= *bottom && elevation < *top)
+                })
+                .count();
+            count as f32 / samples.len() as f32
+        };
+        let highest = elevations[elevations.len() - 1].elevation;
+        let mut low = lowest - samples[samples.len() - 1];
+        let mut high = highest - samples[0];
+        for _ in 0..SOLVER_ITERATIONS {
+            let middle = low.lerp(high, 0.5);
+            if covered_fraction(middle) > self.fraction {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        Some(low.lerp(high, 0.5))
+    }
+}
+
+/// Returns the value `fraction` of the way through the sorted `samples`
+pub(crate) fn quantile(
+    samples: &[Length<f32, Kilometers>],
+    fraction: f32,
+) -> Length<f32, Kilometers> {
+    let position = fraction.clamp(0., 1.) * (samples.len() - 1) as f32;
+    let index = position.floor() as usize;
+    match samples.get(index + 1) {
+        Some(next) => samples[index].lerp(*next, position - index as f32),
+        None => samples[index],
+    }
+}
+
+/// Divides the elevations between the lowest and highest ElevationColors into the bands
+/// each one colors, returned from lowest to highest as `(top, index)`. Since
+/// `GenerationAlgorithm::V1`, each ElevationColor colors the elevations closest to it.
+/// `V0` colors the far half of the gap to each neighbor instead.
+pub(crate) fn bands<Kind>(
+    elevations: &[ElevationColor<Kind>],
+    algorithm: GenerationAlgorithm,
+) -> Vec<(Length<f32, Kilometers>, usize)> {
+    let mut bands = Vec::new();
+    for (index, pair) in elevations.windows(2).enumerate() {
+        let middle = pair[0].elevation.lerp(pair[1].elevation, 0.5);
+        match algorithm {
+            GenerationAlgorithm::V0 => {
+                bands.push((middle, index + 1));
+                bands.push((pair[1].elevation, index));
+            }
+            GenerationAlgorithm::V1 => bands.push((middle, index)),
+        }
+    }
+    if let (GenerationAlgorithm::V1, Some(last)) = (algorithm, elevations.last()) {
+        bands.push((last.elevation, elevations.len() - 1));
+    }
+    bands
+}
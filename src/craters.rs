@@ -0,0 +1,138 @@
+use crate::types::{Kilometers, UnitSphere};
+use euclid::{Length, Vector3D};
+use rand::Rng;
+
+/// Settings controlling how impact craters are scattered across a planet's surface
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Craters {
+    /// How many craters to place on the whole sphere
+    pub count: u32,
+
+    /// The radius of the smallest crater
+    pub min_radius: Length<f32, Kilometers>,
+
+    /// The radius of the largest crater
+    pub max_radius: Length<f32, Kilometers>,
+
+    /// The exponent of the power-law size distribution. The number of craters larger
+    /// than a radius `r` is proportional to `r ^ -exponent`, so larger values produce
+    /// fewer large craters.
+    pub exponent: f32,
+
+    /// How deep the bowl is at its center, as a fraction of the crater's radius
+    pub depth: f32,
+
+    /// How high the rim is raised above the surrounding surface, as a fraction of the
+    /// crater's radius
+    pub rim_height: f32,
+
+    /// How much of the rim's height is thrown outwards as an ejecta blanket. 0.0 disables
+    /// ejecta, and 1.0 replaces the rim's falloff with ejecta entirely.
+    pub ejecta: f32,
+}
+
+impl Default for Craters {
+    fn default() -> Self {
+        Self {
+            count: 150,
+            min_radius: Kilometers::new(50.),
+            max_radius: Kilometers::new(800.),
+            exponent: 1.5,
+            depth: 1.,
+            rim_height: 0.3,
+            ejecta: 0.3,
+        }
+    }
+}
+
+/// A single impact crater
+#[derive(Clone, Copy, Debug)]
+pub struct Crater {
+    /// The point on the unit sphere at the center of the crater
+    pub center: Vector3D<f32, UnitSphere>,
+
+    /// The radius of the crater's rim
+    pub radius: Length<f32, Kilometers>,
+}
+
+/// How far past the rim a crater can influence the surface, relative to its radius
+const MAX_INFLUENCE: f32 = 3.;
+
+/// How wide the falloff of the rim is, relative to the crater's radius
+const RIM_WIDTH: f32 = 0.25;
+
+impl Craters {
+    /// Places `count` craters randomly on a sphere of `planet_radius`, sizing them with a
+    /// truncated power-law distribution
+    pub fn place<R: Rng>(
+        &self,
+        rng: &mut R,
+        planet_radius: Length<f32, Kilometers>,
+    ) -> Vec<Crater> {
+        let min_radius = self.min_radius.get().max(f32::EPSILON);
+        let max_radius = self.max_radius.get().max(min_radius);
+        let exponent = self.exponent.max(f32::EPSILON);
+        let min_term = min_radius.powf(-exponent);
+        let max_term = max_radius.powf(-exponent);
+
+        let mut craters = (0..self.count)
+            .map(|_| {
+                // Uniformly distributed over the sphere's surface
+                let z = rng.gen_range(-1.0f32..1.);
+                let longitude = rng.gen_range(0.0f32..std::f32::consts::TAU);
+                let ring = (1. - z * z).sqrt();
+                let center = Vector3D::new(ring * longitude.cos(), ring * longitude.sin(), z);
+
+                // Inverse of the cumulative distribution of a truncated power law
+                let size = rng.gen_range(0.0f32..1.);
+                let radius = (min_term - size * (min_term - max_term)).powf(-1. / exponent);
+                Crater {
+                    center,
+                    radius: Kilometers::new(radius.min(planet_radius.get())),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Larger craters are older on average, and smaller craters are carved on top of them
+        craters.sort_by(|a, b| b.radius.partial_cmp(&a.radius).unwrap());
+        craters
+    }
+
+    /// Returns the change in elevation `crater` makes at `surface_point` on a sphere
+    /// of `planet_radius`
+    pub fn elevation_change(
+        &self,
+        crater: &Crater,
+        surface_point: Vector3D<f32, UnitSphere>,
+        planet_radius: Length<f32, Kilometers>,
+    ) -> Length<f32, Kilometers> {
+        let radius = crater.radius.get();
+        let angle = surface_point.dot(crater.center).clamp(-1., 1.).acos();
+        let distance = angle * planet_radius.get() / radius;
+        if distance >= MAX_INFLUENCE {
+            return Length::default();
+        }
+
+        let rim = self.rim_height * radius;
+        let change = if distance < 1. {
+            // A parabolic bowl that meets the top of the rim at its edge
+            self.depth * radius * (distance * distance - 1.) + rim
+        } else {
+            let falloff = (-((distance - 1.) / RIM_WIDTH).powi(2)).exp();
+            let ejecta = distance.powi(-3) * (1. - (distance - 1.) / (MAX_INFLUENCE - 1.));
+            rim * ((1. - self.ejecta) * falloff + self.ejecta * ejecta)
+        };
+
+        Kilometers::new(change)
+    }
+
+    /// The largest angle from a crater's center, in radians, that it can influence
+    pub(crate) fn influence_angle(crater: &Crater, planet_radius: Length<f32, Kilometers>) -> f32 {
+        (crater.radius.get() * MAX_INFLUENCE / planet_radius.get()).min(std::f32::consts::PI)
+    }
+}
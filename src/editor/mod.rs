@@ -3,6 +3,7 @@ mod window;
 use crate::cli::args::Edit;
 use kludgine::prelude::*;
 
-pub(crate) fn run(opts: Edit) -> ! {
-    SingleWindowApplication::run(window::EditorWindow::new(opts));
+pub(crate) fn run(opts: Edit) -> anyhow::Result<()> {
+    let definition = opts.command.clone().unwrap_or_default().into_definition()?;
+    SingleWindowApplication::run(window::EditorWindow::new(opts, definition));
 }
@@ -3,13 +3,13 @@ use uuid::Uuid;
 use std::time::Duration;
 
 use crate::{
-    cli::args::{Lightable, Edit, PlanetCommand},
+    cli::{args::{Lightable, Edit}, definition::PlanetDefinition},
     planet::{Light, Planet}, 
-    coloring::Earthlike,
+    coloring::DynamicKind,
 };
 
 pub struct EditorWindow {
-    planet: Planet<Earthlike>,
+    planet: Planet<DynamicKind>,
     resolution: u32,
     image: Entity<Image>,
     seed_label: Entity<Label>,
@@ -18,15 +18,11 @@ pub struct EditorWindow {
 }
 
 impl EditorWindow {
-    pub(crate) fn new(options: Edit) -> Self {
-        let light = options.light();
-        let planet = match options.command.unwrap_or_default() {
-            PlanetCommand::New(options) => options.into(),
-        };
-
-        let resolution = options.resolution.unwrap_or(128);
+    pub(crate) fn new(options: Edit, definition: PlanetDefinition<DynamicKind>) -> Self {
+        let light = options.light().or(definition.light);
+        let resolution = options.resolution.or(definition.resolution).unwrap_or(128);
         Self {
-            planet,
+            planet: definition.planet,
             resolution,
             image: Default::default(),
             seed_label: Default::default(),
@@ -0,0 +1,299 @@
+use crate::types::{Kilometers, LatLong, UnitSphere};
+use euclid::{default::Vector2D, Angle, Length, Vector3D};
+use rand::Rng;
+
+/// Settings for simulating hydraulic and thermal erosion over a planet's elevation. The
+/// elevation is rasterized to an `ElevationGrid` before the simulation runs, and the
+/// terrain samples the eroded grid instead of the noise.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Erosion {
+    /// The number of cells around the equator of the grid. The grid is half as tall.
+    pub resolution: u32,
+
+    /// How many water droplets to simulate
+    pub droplets: u32,
+
+    /// The maximum number of steps a droplet travels before it evaporates completely
+    pub droplet_lifetime: u32,
+
+    /// How much a droplet keeps moving in its current direction instead of downhill,
+    /// from 0.0 to 1.0
+    pub inertia: f32,
+
+    /// How much sediment a droplet can carry, relative to its speed and water
+    pub sediment_capacity: f32,
+
+    /// How quickly a droplet picks up sediment when it has spare capacity, from 0.0 to 1.0
+    pub erosion_rate: f32,
+
+    /// How quickly a droplet drops sediment when it is over capacity, from 0.0 to 1.0
+    pub deposition_rate: f32,
+
+    /// How much of a droplet's water evaporates each step, from 0.0 to 1.0
+    pub evaporation: f32,
+
+    /// How many passes of thermal erosion to run after the droplets
+    pub thermal_iterations: u32,
+
+    /// The steepest height difference between neighboring cells before material slides downhill
+    pub talus: Length<f32, Kilometers>,
+
+    /// How much of the material above the `talus` slides each pass, from 0.0 to 1.0
+    pub thermal_rate: f32,
+}
+
+impl Default for Erosion {
+    fn default() -> Self {
+        Self {
+            resolution: 512,
+            droplets: 50_000,
+            droplet_lifetime: 30,
+            inertia: 0.05,
+            sediment_capacity: 4.,
+            erosion_rate: 0.3,
+            deposition_rate: 0.3,
+            evaporation: 0.01,
+            thermal_iterations: 20,
+            talus: Kilometers::new(100.),
+            thermal_rate: 0.5,
+        }
+    }
+}
+
+const GRAVITY: f32 = 4.;
+const MIN_SEDIMENT_CAPACITY: f32 = 0.01;
+
+/// Elevations stored in an equirectangular grid covering the whole sphere. Rows run from
+/// the north pole to the south pole, and columns run from -180 to 180 degrees of longitude.
+#[derive(Clone, Debug)]
+pub struct ElevationGrid {
+    pub width: u32,
+    pub height: u32,
+    pub elevations: Vec<f32>,
+}
+
+impl ElevationGrid {
+    /// Creates a grid by sampling `elevation_at` at the center of each cell
+    pub fn from_fn<F: Fn(Vector3D<f32, UnitSphere>) -> Length<f32, Kilometers>>(
+        width: u32,
+        height: u32,
+        elevation_at: F,
+    ) -> Self {
+        let width = width.max(2);
+        let height = height.max(2);
+        let mut elevations = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let position = Self::cell_position(width, height, x as f32 + 0.5, y as f32 + 0.5);
+                elevations.push(elevation_at(position.to_surface_point()).get());
+            }
+        }
+
+        Self {
+            width,
+            height,
+            elevations,
+        }
+    }
+
+    fn cell_position(width: u32, height: u32, x: f32, y: f32) -> LatLong {
+        LatLong::new(
+            Angle::degrees(90. - y / height as f32 * 180.),
+            Angle::degrees(x / width as f32 * 360. - 180.),
+        )
+    }
+
+    /// The position of `surface_point` in grid cells. Cell centers are at half-cell offsets.
+    fn grid_position(&self, surface_point: Vector3D<f32, UnitSphere>) -> Vector2D<f32> {
+        let position = LatLong::from_surface_point(surface_point);
+        Vector2D::new(
+            (position.longitude.to_degrees() + 180.) / 360. * self.width as f32,
+            (90. - position.latitude.to_degrees()) / 180. * self.height as f32,
+        )
+    }
+
+    fn index(&self, x: i64, y: i64) -> usize {
+        let x = x.rem_euclid(self.width as i64);
+        let y = y.clamp(0, self.height as i64 - 1);
+        (y * self.width as i64 + x) as usize
+    }
+
+    /// The elevation at `surface_point`, bilinearly interpolated between the nearest cells
+    pub fn sample(&self, surface_point: Vector3D<f32, UnitSphere>) -> Length<f32, Kilometers> {
+        let position = self.grid_position(surface_point) - Vector2D::new(0.5, 0.5);
+        let (height, _) = self.height_and_gradient(position.x, position.y);
+        Kilometers::new(height)
+    }
+
+    /// The interpolated height and its gradient at a position measured from cell centers
+    fn height_and_gradient(&self, x: f32, y: f32) -> (f32, Vector2D<f32>) {
+        let cell_x = x.floor();
+        let cell_y = y.floor();
+        let offset_x = x - cell_x;
+        let offset_y = y - cell_y;
+        let (cell_x, cell_y) = (cell_x as i64, cell_y as i64);
+
+        let north_west = self.elevations[self.index(cell_x, cell_y)];
+        let north_east = self.elevations[self.index(cell_x + 1, cell_y)];
+        let south_west = self.elevations[self.index(cell_x, cell_y + 1)];
+        let south_east = self.elevations[self.index(cell_x + 1, cell_y + 1)];
+
+        let gradient = Vector2D::new(
+            (north_east - north_west) * (1. - offset_y) + (south_east - south_west) * offset_y,
+            (south_west - north_west) * (1. - offset_x) + (south_east - north_east) * offset_x,
+        );
+        let height = north_west * (1. - offset_x) * (1. - offset_y)
+            + north_east * offset_x * (1. - offset_y)
+            + south_west * (1. - offset_x) * offset_y
+            + south_east * offset_x * offset_y;
+
+        (height, gradient)
+    }
+
+    /// Adds `amount` to the four cells surrounding a position, weighted by how close
+    /// the position is to each of them
+    fn deposit(&mut self, x: f32, y: f32, amount: f32) {
+        let cell_x = x.floor();
+        let cell_y = y.floor();
+        let offset_x = x - cell_x;
+        let offset_y = y - cell_y;
+        let (cell_x, cell_y) = (cell_x as i64, cell_y as i64);
+
+        let north_west = self.index(cell_x, cell_y);
+        let north_east = self.index(cell_x + 1, cell_y);
+        let south_west = self.index(cell_x, cell_y + 1);
+        let south_east = self.index(cell_x + 1, cell_y + 1);
+        self.elevations[north_west] += amount * (1. - offset_x) * (1. - offset_y);
+        self.elevations[north_east] += amount * offset_x * (1. - offset_y);
+        self.elevations[south_west] += amount * (1. - offset_x) * offset_y;
+        self.elevations[south_east] += amount * offset_x * offset_y;
+    }
+}
+
+impl Erosion {
+    /// Rasterizes `elevation_at` into a grid and erodes it. The result only depends on
+    /// `elevation_at` and the values produced by `rng`.
+    pub fn erode<R, F>(&self, rng: &mut R, elevation_at: F) -> ElevationGrid
+    where
+        R: Rng,
+        F: Fn(Vector3D<f32, UnitSphere>) -> Length<f32, Kilometers>,
+    {
+        let mut grid = ElevationGrid::from_fn(self.resolution, self.resolution / 2, elevation_at);
+
+        // The simulation's constants are tuned for heights between 0.0 and 1.0
+        let lowest = grid.elevations.iter().copied().fold(f32::MAX, f32::min);
+        let highest = grid.elevations.iter().copied().fold(f32::MIN, f32::max);
+        let span = (highest - lowest).max(f32::EPSILON);
+        for elevation in grid.elevations.iter_mut() {
+            *elevation = (*elevation - lowest) / span;
+        }
+
+        self.hydraulic(rng, &mut grid);
+        self.thermal(&mut grid, self.talus.get() / span);
+
+        for elevation in grid.elevations.iter_mut() {
+            *elevation = *elevation * span + lowest;
+        }
+
+        grid
+    }
+
+    fn hydraulic<R: Rng>(&self, rng: &mut R, grid: &mut ElevationGrid) {
+        let width = grid.width as f32;
+        let height = grid.height as f32;
+
+        for _ in 0..self.droplets {
+            // Spread the droplets evenly over the sphere rather than over the grid
+            let latitude = rng.gen_range(-1.0f32..1.).asin().to_degrees();
+            let mut x = rng.gen_range(0.0..width) - 0.5;
+            let mut y = (90. - latitude) / 180. * height - 0.5;
+            let mut direction = Vector2D::<f32>::zero();
+            let mut speed = 1.;
+            let mut water = 1.;
+            let mut sediment = 0.;
+
+            for _ in 0..self.droplet_lifetime {
+                let (current_height, gradient) = grid.height_and_gradient(x, y);
+
+                direction = direction * self.inertia - gradient * (1. - self.inertia);
+                let length = direction.length();
+                if length <= f32::EPSILON {
+                    break;
+                }
+                direction /= length;
+
+                let (new_x, new_y) = (x + direction.x, y + direction.y);
+                if new_y < -0.5 || new_y > height - 0.5 {
+                    break;
+                }
+
+                let (new_height, _) = grid.height_and_gradient(new_x, new_y);
+                let height_change = new_height - current_height;
+
+                let capacity = (-height_change * speed * water * self.sediment_capacity)
+                    .max(MIN_SEDIMENT_CAPACITY);
+
+                if sediment > capacity || height_change > 0. {
+                    // Fill the pit the droplet is climbing out of, or drop the excess sediment
+                    let amount = if height_change > 0. {
+                        height_change.min(sediment)
+                    } else {
+                        (sediment - capacity) * self.deposition_rate
+                    };
+                    sediment -= amount;
+                    grid.deposit(x, y, amount);
+                } else {
+                    // Never dig deeper than the height change, or the droplet carves a hole
+                    let amount = ((capacity - sediment) * self.erosion_rate).min(-height_change);
+                    sediment += amount;
+                    grid.deposit(x, y, -amount);
+                }
+
+                speed = (speed * speed + height_change * GRAVITY).max(0.).sqrt();
+                water *= 1. - self.evaporation;
+                x = new_x.rem_euclid(width);
+                y = new_y;
+            }
+        }
+    }
+
+    fn thermal(&self, grid: &mut ElevationGrid, talus: f32) {
+        let mut changes = vec![0.; grid.elevations.len()];
+        for _ in 0..self.thermal_iterations {
+            for change in changes.iter_mut() {
+                *change = 0.;
+            }
+
+            for y in 0..grid.height as i64 {
+                for x in 0..grid.width as i64 {
+                    let index = grid.index(x, y);
+                    let elevation = grid.elevations[index];
+                    for (neighbor_x, neighbor_y) in [(x + 1, y), (x, y + 1)].iter().copied() {
+                        if neighbor_y >= grid.height as i64 {
+                            continue;
+                        }
+
+                        let neighbor = grid.index(neighbor_x, neighbor_y);
+                        let difference = elevation - grid.elevations[neighbor];
+                        if difference.abs() > talus {
+                            let amount = (difference.abs() - talus) / 2.
+                                * self.thermal_rate
+                                * difference.signum();
+                            changes[index] -= amount;
+                            changes[neighbor] += amount;
+                        }
+                    }
+                }
+            }
+
+            for (elevation, change) in grid.elevations.iter_mut().zip(changes.iter()) {
+                *elevation += change;
+            }
+        }
+    }
+}
@@ -3,13 +3,44 @@ pub use image;
 pub use palette;
 
 pub use self::{
-    coloring::ElevationColor,
-    planet::{Light, Planet},
+    background::Background,
+    banding::Banding,
+    canvas::Canvas,
+    coloring::{DynamicKind, ElevationColor, Palette},
+    coverage::Coverage,
+    craters::Craters,
+    erosion::Erosion,
+    moons::Moon,
+    orbit::Orbit,
+    planet::{GenerationAlgorithm, Light, Planet},
+    quantize::Quantize,
+    remap::ElevationRemap,
+    rivers::Rivers,
+    shape::Shape,
+    star::Star,
+    system::System,
+    tectonics::Tectonics,
     terrain::Terrain,
-    types::Kilometers,
+    types::{Kilometers, LatLong, Pixels, UnitSphere},
 };
 
+pub mod animation;
+pub mod background;
+pub mod banding;
+pub mod canvas;
 pub mod coloring;
+pub mod coverage;
+pub mod craters;
+pub mod erosion;
+pub mod moons;
+pub mod orbit;
 pub mod planet;
+pub mod quantize;
+pub mod remap;
+pub mod rivers;
+pub mod shape;
+pub mod star;
+pub mod system;
+pub mod tectonics;
 mod terrain;
 mod types;
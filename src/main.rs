@@ -1,9 +1,25 @@
+pub mod animation;
+pub mod background;
+pub mod banding;
+pub mod canvas;
 #[cfg(feature = "cli")]
 mod cli;
 pub mod coloring;
+pub mod coverage;
+pub mod craters;
+pub mod erosion;
 #[cfg(feature = "editor")]
 mod editor;
+pub mod moons;
+pub mod orbit;
 pub mod planet;
+pub mod quantize;
+pub mod remap;
+pub mod rivers;
+pub mod shape;
+pub mod star;
+pub mod system;
+pub mod tectonics;
 mod terrain;
 mod types;
 
@@ -0,0 +1,222 @@
+use crate::{
+    canvas::Canvas,
+    orbit::Orbit,
+    planet::{GeneratedPlanet, Light, ParallelSafe, Planet, SurfaceDefinition},
+    terrain::Terrain,
+    types::{Kilometers, Pixels},
+};
+use euclid::{Point2D, Scale, Vector2D, Vector3D};
+use palette::Srgb;
+use std::{collections::HashMap, hash::Hash, time::Duration};
+
+/// A natural satellite of a planet
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "Kind: serde::Serialize",
+        deserialize = "Kind: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Moon<Kind> {
+    /// The moon itself. Its `origin` and `orbit` are ignored, as it follows `orbit` around
+    /// its parent instead.
+    pub planet: Planet<Kind>,
+
+    /// The moon's orbit around its parent
+    pub orbit: Orbit,
+}
+
+impl<Kind> Moon<Kind> {
+    pub fn new(planet: Planet<Kind>, orbit: Orbit) -> Self {
+        Self { planet, orbit }
+    }
+}
+
+/// How bright a surface in the shadow of another body is, compared to when it is lit
+const ECLIPSE_BRIGHTNESS: f32 = 0.1;
+
+/// A planet or moon placed in the composite render
+struct Body<Kind> {
+    terrain: Terrain<Kind>,
+
+    /// The body's center relative to the parent's center. The z axis points towards the viewer.
+    offset: Vector3D<f32, Kilometers>,
+}
+
+impl<Kind> Body<Kind> {
+    fn radius(&self) -> f32 {
+        self.terrain.radius.get()
+    }
+
+    /// The farthest the body reaches from its center, including an irregular shape
+    fn extent(&self) -> f32 {
+        match &self.terrain.shape {
+            Some(shape) => self.radius() * shape.max_extent(),
+            None => self.radius(),
+        }
+    }
+
+    /// How far towards the viewer the body's surface is at `point`, if it covers `point`
+    fn surface_height(&self, point: Vector2D<f32, Kilometers>) -> Option<f32> {
+        let from_center = (point - self.offset.xy()).square_length();
+        let radius = self.radius();
+        if from_center < radius * radius {
+            Some(self.offset.z + (radius * radius - from_center).sqrt())
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the body blocks the ray from `point` in `direction`
+    fn blocks(
+        &self,
+        point: Vector3D<f32, Kilometers>,
+        direction: Vector3D<f32, Kilometers>,
+    ) -> bool {
+        let to_center = self.offset - point;
+        let along = to_center.dot(direction);
+        along > 0. && to_center.square_length() - along * along < self.radius() * self.radius()
+    }
+}
+
+impl<Kind> Planet<Kind>
+where
+    Kind: SurfaceDefinition + Clone + Hash + Eq,
+{
+    /// Generates an image of `pixels` wide, and `pixels` tall, of the planet and its moons
+    /// as they are at `time`. Every body is drawn at the same scale, which is chosen to fit
+    /// the moons' orbits in the image. If a light is provided, bodies that pass between
+    /// another body and the star cast their shadow on it. Only direct moons are drawn.
+    pub fn generate_with_moons(
+        &self,
+        pixels: u32,
+        time: Duration,
+        sun: &Option<Light>,
+    ) -> GeneratedPlanet<Kind>
+    where
+        Kind: ParallelSafe,
+    {
+        let parent = self.at_time(time);
+        let mut bodies = vec![Body {
+            terrain: Terrain::generate(&parent),
+            offset: Vector3D::zero(),
+        }];
+        for moon in &self.moons {
+            let mut planet = moon.planet.at_time(time);
+            let position = moon.orbit.position_at(time).to_vector();
+            planet.origin = parent.origin + position;
+            bodies.push(Body {
+                terrain: Terrain::generate(&planet),
+                offset: position.extend(moon.orbit.height_at(time).get()),
+            });
+        }
+
+        let extent = bodies
+            .iter()
+            .map(|body| body.offset.xy().length() + body.extent())
+            .fold(0., f32::max);
+        let scale = Scale::<f32, Pixels, Kilometers>::new(extent / (pixels as f32 / 2.));
+        let center = Canvas::square(pixels).center();
+        // Every body is rendered the same way as on its own, placed at its offset
+        let placements = bodies
+            .iter()
+            .map(|body| {
+                body.terrain
+                    .placement_at(center + body.offset.xy() / scale, scale)
+            })
+            .collect::<Vec<_>>();
+        // Starlight comes from the star at the center of the system
+        let towards_star = (-parent.origin.to_vector()).extend(0.).normalize();
+
+        let render_row = |y: u32| {
+            let mut stats = HashMap::new();
+            let colors = (0..pixels)
+                .map(|x| {
+                    let point = (Point2D::new(x as f32, y as f32) - center) * scale;
+                    let mut covering = bodies
+                        .iter()
+                        .zip(&placements)
+                        .enumerate()
+                        .filter_map(|(index, (body, placement))| {
+                            let (color, kind) = body.terrain.render_pixel(placement, x, y, sun);
+                            let height = body.surface_height(point).unwrap_or(body.offset.z);
+                            kind.map(|kind| (index, height, color, kind))
+                        })
+                        .collect::<Vec<_>>();
+                    // The body closest to the viewer is drawn
+                    covering.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+                    let behind = covering.len() > 1;
+                    let (index, height, mut color, kind) = match covering.into_iter().next() {
+                        Some(front) => front,
+                        None => return [0; 4],
+                    };
+
+                    if sun.is_some() {
+                        let surface_point = point.extend(height);
+                        let eclipsed = bodies.iter().enumerate().any(|(other, body)| {
+                            other != index && body.blocks(surface_point, towards_star)
+                        });
+                        if eclipsed {
+                            color = darken(color);
+                        }
+                    }
+
+                    // Only antialias the edge if no other body is behind it
+                    if behind {
+                        color[3] = 255;
+                    }
+
+                    stats
+                        .entry(kind)
+                        .and_modify(|count| *count += 1)
+                        .or_insert(1);
+                    color
+                })
+                .collect::<Vec<_>>();
+            (colors, stats)
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let rows = (0..pixels).map(render_row);
+        #[cfg(feature = "parallel")]
+        let rows = {
+            use rayon::prelude::*;
+            (0..pixels)
+                .into_par_iter()
+                .map(render_row)
+                .collect::<Vec<_>>()
+        };
+
+        let mut image = image::RgbaImage::new(pixels, pixels);
+        let mut stats = HashMap::new();
+        for (y, (colors, row_stats)) in rows.into_iter().enumerate() {
+            for (x, color) in colors.into_iter().enumerate() {
+                image.put_pixel(x as u32, y as u32, image::Rgba(color));
+            }
+            for (kind, count) in row_stats {
+                *stats.entry(kind).or_insert(0) += count;
+            }
+        }
+
+        // The parent is always the first body
+        let rivers = bodies.swap_remove(0).terrain.rivers;
+        GeneratedPlanet {
+            image,
+            stats,
+            rivers,
+        }
+    }
+}
+
+/// Darkens `color`, keeping its alpha
+fn darken(color: [u8; 4]) -> [u8; 4] {
+    let [red, green, blue, alpha] = color;
+    let linear = Srgb::new(red, green, blue)
+        .into_format::<f32>()
+        .into_linear()
+        * ECLIPSE_BRIGHTNESS;
+    let darkened: Srgb<u8> = Srgb::from_linear(linear).into_format();
+    [darkened.red, darkened.green, darkened.blue, alpha]
+}
@@ -0,0 +1,131 @@
+use crate::types::Kilometers;
+use euclid::{Angle, Length, Point2D};
+use std::{f64::consts::TAU, time::Duration};
+
+/// A Keplerian orbit around the star at the center of the system. The system is viewed
+/// from above, so inclined orbits appear squashed along the y axis.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Orbit {
+    /// Half of the longest diameter of the orbit's ellipse
+    pub semi_major_axis: Length<f32, Kilometers>,
+
+    /// How elongated the orbit is, from 0.0 for a circle up to (but not including) 1.0
+    pub eccentricity: f32,
+
+    /// How far the orbit is tilted away from the view, around the x axis
+    pub inclination: Angle<f32>,
+
+    /// The angle from the x axis to the point where the orbit is closest to the star
+    pub argument_of_periapsis: Angle<f32>,
+
+    /// How long one full orbit takes
+    pub period: Duration,
+
+    /// How far along the orbit the body is at time zero, as if the orbit were a circle
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub mean_anomaly_at_epoch: Angle<f32>,
+}
+
+/// The most iterations used to solve Kepler's equation
+const KEPLER_ITERATIONS: u32 = 16;
+
+impl Orbit {
+    /// A circular orbit at `distance` that is at `angle` at time zero, the same position
+    /// `calculate_origin(angle, distance)` returns
+    pub fn circular(
+        distance: Length<f32, Kilometers>,
+        angle: Angle<f32>,
+        period: Duration,
+    ) -> Self {
+        Self {
+            semi_major_axis: distance,
+            eccentricity: 0.,
+            inclination: Angle::zero(),
+            argument_of_periapsis: Angle::zero(),
+            period,
+            mean_anomaly_at_epoch: angle,
+        }
+    }
+
+    /// How far along the orbit the body is at `time`, as if the orbit were a circle
+    fn mean_anomaly_at(&self, time: Duration) -> f64 {
+        let period = self.period.as_secs_f64();
+        let progress = if period > 0. {
+            (time.as_secs_f64() / period).fract()
+        } else {
+            0.
+        };
+        self.mean_anomaly_at_epoch.radians as f64 + progress * TAU
+    }
+
+    /// Solves Kepler's equation for the eccentric anomaly at `time`
+    fn eccentric_anomaly_at(&self, time: Duration) -> f64 {
+        let eccentricity = self.eccentricity() as f64;
+        let mean_anomaly = self.mean_anomaly_at(time);
+        let mut anomaly = if eccentricity > 0.8 {
+            std::f64::consts::PI
+        } else {
+            mean_anomaly
+        };
+        for _ in 0..KEPLER_ITERATIONS {
+            let step = (anomaly - eccentricity * anomaly.sin() - mean_anomaly)
+                / (1. - eccentricity * anomaly.cos());
+            anomaly -= step;
+            if step.abs() < 1e-10 {
+                break;
+            }
+        }
+        anomaly
+    }
+
+    fn eccentricity(&self) -> f32 {
+        self.eccentricity.clamp(0., 0.99)
+    }
+
+    /// The distance from the star at `time`
+    pub fn distance_at(&self, time: Duration) -> Length<f32, Kilometers> {
+        let anomaly = self.eccentric_anomaly_at(time);
+        self.semi_major_axis * (1. - self.eccentricity() * anomaly.cos() as f32)
+    }
+
+    /// The distance from the star and the angle from the x axis within the orbital plane
+    /// at `time`
+    fn polar_position_at(&self, time: Duration) -> (f64, f64) {
+        let eccentricity = self.eccentricity() as f64;
+        let anomaly = self.eccentric_anomaly_at(time);
+        let true_anomaly = 2.
+            * ((1. + eccentricity).sqrt() * (anomaly / 2.).sin())
+                .atan2((1. - eccentricity).sqrt() * (anomaly / 2.).cos());
+        let distance = self.distance_at(time).get() as f64;
+        (
+            distance,
+            self.argument_of_periapsis.radians as f64 + true_anomaly,
+        )
+    }
+
+    /// The position relative to the star at `time`, as seen from above
+    pub fn position_at(&self, time: Duration) -> Point2D<f32, Kilometers> {
+        let (distance, angle) = self.polar_position_at(time);
+        Point2D::new(
+            (distance * angle.cos()) as f32,
+            (distance * angle.sin() * (self.inclination.radians as f64).cos()) as f32,
+        )
+    }
+
+    /// How far above the star the body is at `time`, towards the viewer. Only inclined
+    /// orbits leave the star's plane.
+    pub fn height_at(&self, time: Duration) -> Length<f32, Kilometers> {
+        let (distance, angle) = self.polar_position_at(time);
+        Length::new((distance * angle.sin() * (self.inclination.radians as f64).sin()) as f32)
+    }
+
+    /// How much starlight reaches the body at `time` compared to at a distance of
+    /// `semi_major_axis`, which follows the inverse square of the distance
+    pub fn insolation_at(&self, time: Duration) -> f32 {
+        (self.semi_major_axis.get() / self.distance_at(time).get()).powi(2)
+    }
+}
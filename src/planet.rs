@@ -1,16 +1,47 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{collections::HashMap, hash::Hash, time::Duration};
 
-use crate::{coloring::ElevationColor, terrain::Terrain, types::Kilometers};
+use crate::{
+    banding::Banding,
+    canvas::Canvas,
+    coloring::{ElevationColor, Sunlike},
+    coverage::Coverage,
+    craters::Craters,
+    erosion::Erosion,
+    moons::Moon,
+    orbit::Orbit,
+    remap::ElevationRemap,
+    rivers::{River, Rivers},
+    shape::Shape,
+    star::Star,
+    tectonics::Tectonics,
+    terrain::Terrain,
+    types::{Kilometers, Pixels},
+};
 use euclid::{Angle, Length, Point2D, Rotation2D};
+use image::{GenericImage, Rgba};
 use palette::Srgb;
 use sorted_vec::partial::SortedVec;
 
 /// A Procedural Planet definition
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "Kind: serde::Serialize",
+        deserialize = "Kind: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Planet<Kind> {
     /// The unique value that is used to seed the random number generator
+    #[cfg_attr(feature = "serialization", serde(with = "seed"))]
     pub seed: u64,
 
+    /// The version of the generation algorithm used to turn the seed and settings into
+    /// terrain. It has no default when deserializing, so that a definition keeps the
+    /// version it was written for when a new one is released.
+    pub algorithm: GenerationAlgorithm,
+
     /// Controls how "busy" the surface is -- a larger amount of chaos means a much bumpier surface
     pub max_chaos: f32,
 
@@ -20,19 +51,142 @@ pub struct Planet<Kind> {
     /// The radius of the planet
     pub radius: Length<f32, Kilometers>,
 
+    /// How far the planet has spun around its axis, which runs from its north pole at the
+    /// top of the image to its south pole at the bottom
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub rotation: Angle<f32>,
+
+    /// If present, `at_time` moves the planet's `origin` along this orbit
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub orbit: Option<Orbit>,
+
+    /// If present, how long the planet takes to spin once around its axis. `at_time`
+    /// advances `rotation` from its current value at this rate.
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub rotation_period: Option<Duration>,
+
+    /// The moons orbiting the planet, drawn by `generate_with_moons`
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub moons: Vec<Moon<Kind>>,
+
     /// The ElevationColors used to generate the terrain
+    #[cfg_attr(feature = "serialization", serde(with = "sorted_colors"))]
     pub colors: SortedVec<ElevationColor<Kind>>,
+
+    /// If present, the planet is rendered as a star: external lighting is ignored, and the
+    /// color comes from the star's temperature rather than `colors`
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub star: Option<Star>,
+
+    /// If present, the surface is stretched into bands of latitude, like a gas giant
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub banding: Option<Banding>,
+
+    /// If present, the planet is rendered as an irregular small body, such as an asteroid,
+    /// instead of a sphere
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub shape: Option<Shape>,
+
+    /// If present, impact craters are carved into the surface
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub craters: Option<Craters>,
+
+    /// If present, continents are laid out by simulating tectonic plates
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub tectonics: Option<Tectonics>,
+
+    /// If present, hydraulic and thermal erosion is simulated over the elevation
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub erosion: Option<Erosion>,
+
+    /// How the raw elevation is mapped onto `colors`
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub remap: ElevationRemap,
+
+    /// If present, the elevation is shifted so that the target fraction of the surface
+    /// is covered by the target kind and every kind below it
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub coverage: Option<Coverage<Kind>>,
+
+    /// If present, rivers are traced from the highlands down to liquid
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub rivers: Option<Rivers<Kind>>,
+}
+
+/// A version of the generation algorithm. Changes to how the base terrain is generated and
+/// colored are released as a new version, so saved planets can keep generating with the
+/// version they were made with. Optional features, such as craters, erosion or rivers,
+/// aren't covered by the version, and their output may change between releases.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum GenerationAlgorithm {
+    /// The algorithm from before generation was versioned. It seeds `SmallRng`, whose
+    /// values differ between platforms, samples flat noise across the disc facing the
+    /// viewer, and picks the farther of the two elevation stops around the elevation.
+    V0,
+    /// Seeds a ChaCha8 random number generator, which produces the same values on
+    /// every platform. Samples the noise on the surface of a sphere, and colors each
+    /// point with the elevation stop closest to it. A larger `Light::sols` lights more
+    /// of the planet, where `V0` darkened it.
+    V1,
+}
+
+impl GenerationAlgorithm {
+    /// The newest version of the algorithm, used by default for new planets
+    pub const LATEST: Self = GenerationAlgorithm::V1;
+}
+
+impl Default for GenerationAlgorithm {
+    fn default() -> Self {
+        Self::LATEST
+    }
 }
 
 pub trait SurfaceDefinition {
     fn max_chaos() -> f32 {
         4.
     }
+
+    /// Returns true if this kind of surface is a liquid, such as an ocean. Rivers
+    /// flow until they reach a liquid.
+    fn is_liquid(&self) -> bool {
+        false
+    }
+
+    /// The name of this kind, used when it's written to a palette
+    fn kind_name(&self) -> String
+    where
+        Self: std::fmt::Debug,
+    {
+        format!("{:?}", self)
+    }
 }
 
+/// Kinds are shared between threads when rendering with the `parallel` feature, which
+/// requires them to be `Send` and `Sync`. Without the feature, every type implements this.
+#[cfg(feature = "parallel")]
+pub trait ParallelSafe: Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> ParallelSafe for T {}
+
+/// Kinds are shared between threads when rendering with the `parallel` feature, which
+/// requires them to be `Send` and `Sync`. Without the feature, every type implements this.
+#[cfg(not(feature = "parallel"))]
+pub trait ParallelSafe {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> ParallelSafe for T {}
+
 pub struct GeneratedPlanet<Kind> {
     pub image: image::RgbaImage,
     pub stats: HashMap<Kind, u32>,
+
+    /// The paths of the rivers flowing across the planet
+    pub rivers: Vec<River>,
 }
 
 impl<Kind> Planet<Kind>
@@ -50,10 +204,24 @@ where
     {
         Self {
             seed,
+            algorithm: GenerationAlgorithm::default(),
             origin,
             radius,
+            rotation: Angle::zero(),
+            orbit: None,
+            rotation_period: None,
+            moons: Vec::new(),
             max_chaos: Kind::max_chaos(),
             colors: SortedVec::from_unsorted(colors.into_iter().collect()),
+            star: None,
+            banding: None,
+            shape: None,
+            craters: None,
+            tectonics: None,
+            erosion: None,
+            remap: ElevationRemap::default(),
+            coverage: None,
+            rivers: None,
         }
     }
 
@@ -66,26 +234,238 @@ where
     ) -> Self {
         Self {
             seed,
+            algorithm: GenerationAlgorithm::default(),
             origin,
             radius,
+            rotation: Angle::zero(),
+            orbit: None,
+            rotation_period: None,
+            moons: Vec::new(),
             max_chaos,
             colors: SortedVec::from_unsorted(colors.into_iter().collect()),
+            star: None,
+            banding: None,
+            shape: None,
+            craters: None,
+            tectonics: None,
+            erosion: None,
+            remap: ElevationRemap::default(),
+            coverage: None,
+            rivers: None,
+        }
+    }
+
+    /// Converts the planet and its moons to another type of kind, using `map` to convert
+    /// each kind
+    pub fn map_kinds<NewKind, F>(self, map: &F) -> Planet<NewKind>
+    where
+        F: Fn(&Kind) -> NewKind,
+    {
+        Planet {
+            seed: self.seed,
+            algorithm: self.algorithm,
+            max_chaos: self.max_chaos,
+            origin: self.origin,
+            radius: self.radius,
+            rotation: self.rotation,
+            orbit: self.orbit,
+            rotation_period: self.rotation_period,
+            moons: self
+                .moons
+                .into_iter()
+                .map(|moon| Moon::new(moon.planet.map_kinds(map), moon.orbit))
+                .collect(),
+            colors: SortedVec::from_unsorted(
+                self.colors
+                    .iter()
+                    .map(|color| ElevationColor {
+                        kind: map(&color.kind),
+                        color: color.color,
+                        elevation: color.elevation,
+                    })
+                    .collect(),
+            ),
+            star: self.star,
+            banding: self.banding,
+            shape: self.shape,
+            craters: self.craters,
+            tectonics: self.tectonics,
+            erosion: self.erosion,
+            remap: self.remap,
+            coverage: self.coverage.map(|coverage| Coverage {
+                kind: map(&coverage.kind),
+                fraction: coverage.fraction,
+            }),
+            rivers: self.rivers.map(|rivers| Rivers {
+                kind: map(&rivers.kind),
+                color: rivers.color,
+                count: rivers.count,
+                source_elevation: rivers.source_elevation,
+                width: rivers.width,
+                step: rivers.step,
+                max_steps: rivers.max_steps,
+            }),
         }
     }
 
     /// Generates an image of `pixels` wide, and `pixels` tall. If a light is provided
     /// a shadow is simulated, and the colors are mixed with the light's color
-    pub fn generate(&self, pixels: u32, sun: &Option<Light>) -> GeneratedPlanet<Kind> {
+    pub fn generate(&self, pixels: u32, sun: &Option<Light>) -> GeneratedPlanet<Kind>
+    where
+        Kind: SurfaceDefinition + ParallelSafe,
+    {
         let terrain = Terrain::generate(self);
         terrain.generate_planet(pixels, sun)
     }
 
+    /// Generates an image the size of `canvas`, with the planet placed and sized as the
+    /// canvas describes
+    pub fn generate_canvas(&self, canvas: &Canvas, sun: &Option<Light>) -> GeneratedPlanet<Kind>
+    where
+        Kind: SurfaceDefinition + ParallelSafe,
+    {
+        let terrain = Terrain::generate(self);
+        terrain.generate_canvas(canvas, sun)
+    }
+
+    /// Renders the planet into `target` with the top left corner of `canvas` at `offset`,
+    /// such as into a cell of a texture atlas. Returns how many pixels of each kind were
+    /// rendered. To render a canvas in tiles, generate a `Terrain` once and call
+    /// `Terrain::render_tile` for each tile.
+    pub fn render_into<I>(
+        &self,
+        canvas: &Canvas,
+        target: &mut I,
+        offset: Point2D<u32, Pixels>,
+        sun: &Option<Light>,
+    ) -> HashMap<Kind, u32>
+    where
+        Kind: SurfaceDefinition + ParallelSafe,
+        I: GenericImage<Pixel = Rgba<u8>>,
+    {
+        Terrain::generate(self).render_into(canvas, target, offset, sun)
+    }
+
+    /// Returns this planet as it is at `time` on the simulation clock, with its `origin`
+    /// on its `orbit` and its `rotation` advanced by its `rotation_period`
+    pub fn at_time(&self, time: Duration) -> Self {
+        let mut planet = self.clone();
+        if let Some(orbit) = &self.orbit {
+            planet.origin = orbit.position_at(time);
+        }
+        if let Some(period) = self.rotation_period.filter(|period| !period.is_zero()) {
+            let turns = (time.as_secs_f64() / period.as_secs_f64()).fract() as f32;
+            planet.rotation = (self.rotation + Angle::two_pi() * turns).signed();
+        }
+        planet
+    }
+
+    /// Returns `light` as it shines on the planet at `time`. On an elliptical orbit, the
+    /// light is brighter near the star and dimmer far from it.
+    pub fn light_at(&self, time: Duration, light: Light) -> Light {
+        match &self.orbit {
+            Some(orbit) => Light {
+                sols: light.sols * orbit.insolation_at(time),
+                ..light
+            },
+            None => light,
+        }
+    }
+
     /// Convience method to calculate the origin of a planet if it orbited in an exact circle at `distance`
     pub fn set_origin_by_angle(&mut self, angle: Angle<f32>, distance: Length<f32, Kilometers>) {
         self.origin = calculate_origin(angle, distance);
     }
 }
 
+impl Planet<Sunlike> {
+    /// Creates a star of `radius` at the center of the system. The `ElevationColor::sunlike()`
+    /// kinds are used to classify the granulation of the surface.
+    pub fn new_star(seed: u64, radius: Length<f32, Kilometers>, star: Star) -> Self {
+        let mut planet =
+            Self::new_from_iter(seed, Point2D::default(), radius, ElevationColor::sunlike());
+        planet.star = Some(star);
+        planet
+    }
+
+    /// The light this star casts on the bodies orbiting it, if this planet is a star
+    pub fn light(&self) -> Option<Light> {
+        self.star.map(|star| star.light(self.radius))
+    }
+}
+
+/// Serializes the sorted colors as a plain list, and sorts them again when deserializing
+#[cfg(feature = "serialization")]
+mod sorted_colors {
+    use crate::coloring::ElevationColor;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use sorted_vec::partial::SortedVec;
+
+    pub fn serialize<S, Kind>(
+        colors: &SortedVec<ElevationColor<Kind>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        Kind: Serialize,
+    {
+        colors.as_slice().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, Kind>(
+        deserializer: D,
+    ) -> Result<SortedVec<ElevationColor<Kind>>, D::Error>
+    where
+        D: Deserializer<'de>,
+        Kind: Deserialize<'de>,
+    {
+        Vec::deserialize(deserializer).map(SortedVec::from_unsorted)
+    }
+}
+
+/// TOML integers are signed 64-bit, so seeds too large to fit are stored as strings
+#[cfg(feature = "serialization")]
+mod seed {
+    use serde::{de, Deserializer, Serializer};
+    use std::{convert::TryFrom, fmt};
+
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        if *seed <= i64::MAX as u64 {
+            serializer.serialize_u64(*seed)
+        } else {
+            serializer.collect_str(seed)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        deserializer.deserialize_any(SeedVisitor)
+    }
+
+    struct SeedVisitor;
+
+    impl<'de> de::Visitor<'de> for SeedVisitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an unsigned 64-bit integer, or a string containing one")
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<u64, E> {
+            Ok(value)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<u64, E> {
+            u64::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+            value
+                .parse()
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+}
+
 pub fn calculate_origin(
     angle: Angle<f32>,
     distance: Length<f32, Kilometers>,
@@ -95,13 +475,20 @@ pub fn calculate_origin(
 }
 
 /// Structure representing a star projecting light. It is not scientific
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
 pub struct Light {
     /// The color of the light. In most cases, you should use a color close to white.
     pub color: Srgb<f32>,
 
     /// The intensity of the light. Because the simulation of light isn't scientific,
     /// this is meant to be a multiplicative factor based on the "feel" of how bright
-    /// an Earth-like planet appears at Earth-like distances.
+    /// an Earth-like planet appears at Earth-like distances. Since
+    /// `GenerationAlgorithm::V1`, larger values light more of the planet.
     pub sols: f32,
 }
 
@@ -0,0 +1,200 @@
+use image::RgbaImage;
+use palette::{Lab, Srgb};
+
+/// Settings for reducing an image to a fixed palette, such as the palette of a game's art
+/// direction. Each pixel is replaced by the palette color that looks closest to it.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Quantize {
+    /// The colors the image is reduced to
+    pub palette: Vec<Srgb<u8>>,
+
+    /// How the difference between a pixel and its palette color is spread to its neighbors
+    #[cfg_attr(feature = "serialization", serde(default))]
+    pub dithering: Dithering,
+}
+
+/// A technique for hiding the banding left by reducing an image to a palette
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Dithering {
+    /// Each pixel becomes its closest color
+    #[default]
+    None,
+    /// Floyd-Steinberg error diffusion, which looks smooth but noisy
+    FloydSteinberg,
+    /// A 4x4 Bayer matrix, which produces the regular patterns common in pixel art
+    Ordered,
+}
+
+impl std::str::FromStr for Dithering {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Dithering::None),
+            "floyd-steinberg" => Ok(Dithering::FloydSteinberg),
+            "ordered" => Ok(Dithering::Ordered),
+            _ => Err(format!(
+                "unknown dithering {:?}, expected none, floyd-steinberg or ordered",
+                s
+            )),
+        }
+    }
+}
+
+/// The 4x4 Bayer threshold matrix
+const BAYER: [[f32; 4]; 4] = [
+    [0., 8., 2., 10.],
+    [12., 4., 14., 6.],
+    [3., 11., 1., 9.],
+    [15., 7., 13., 5.],
+];
+
+impl Quantize {
+    /// Creates settings that reduce an image to `palette` without dithering
+    pub fn new<I: IntoIterator<Item = Srgb<u8>>>(palette: I) -> Self {
+        Self {
+            palette: palette.into_iter().collect(),
+            dithering: Dithering::default(),
+        }
+    }
+
+    /// The 16 colors of the PICO-8 fantasy console
+    pub fn pico8() -> Self {
+        Self::new(
+            [
+                0x000000, 0x1d2b53, 0x7e2553, 0x008751, 0xab5236, 0x5f574f, 0xc2c3c7, 0xfff1e8,
+                0xff004d, 0xffa300, 0xffec27, 0x00e436, 0x29adff, 0x83769c, 0xff77a8, 0xffccaa,
+            ]
+            .iter()
+            .map(|rgb| Srgb::new((rgb >> 16) as u8, (rgb >> 8) as u8, *rgb as u8)),
+        )
+    }
+
+    /// Reduces every visible pixel of `image` to a color of the palette. Transparency is
+    /// left unchanged. Does nothing if the palette is empty.
+    pub fn apply(&self, image: &mut RgbaImage) {
+        if self.palette.is_empty() {
+            return;
+        }
+        let palette = self
+            .palette
+            .iter()
+            .map(|color| to_lab(*color))
+            .collect::<Vec<_>>();
+
+        match self.dithering {
+            Dithering::None => {
+                for pixel in image.pixels_mut().filter(|pixel| pixel[3] > 0) {
+                    let color = to_lab(Srgb::new(pixel[0], pixel[1], pixel[2]));
+                    self.set(pixel, nearest(&palette, color));
+                }
+            }
+            Dithering::FloydSteinberg => self.diffuse(image, &palette),
+            Dithering::Ordered => {
+                // Half the distance between neighboring colors of an evenly spread palette
+                let spread = 0.5 / (self.palette.len() as f32).cbrt();
+                for (x, y, pixel) in image.enumerate_pixels_mut() {
+                    if pixel[3] == 0 {
+                        continue;
+                    }
+                    let threshold = (BAYER[y as usize % 4][x as usize % 4] + 0.5) / 16. - 0.5;
+                    let offset = threshold * spread;
+                    let color: Srgb<f32> = Srgb::new(pixel[0], pixel[1], pixel[2]).into_format();
+                    let color = Srgb::new(
+                        (color.red + offset).clamp(0., 1.),
+                        (color.green + offset).clamp(0., 1.),
+                        (color.blue + offset).clamp(0., 1.),
+                    );
+                    self.set(pixel, nearest(&palette, Lab::from(color.into_linear())));
+                }
+            }
+        }
+    }
+
+    /// Floyd-Steinberg dithering. Colors are matched in Lab space, but the error is
+    /// spread in sRGB, which keeps it from overshooting into unrelated hues.
+    fn diffuse(&self, image: &mut RgbaImage, palette: &[Lab]) {
+        let (width, height) = image.dimensions();
+        let mut colors = image
+            .pixels()
+            .map(|pixel| [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32])
+            .collect::<Vec<_>>();
+
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = image.get_pixel_mut(x, y);
+                if pixel[3] == 0 {
+                    continue;
+                }
+                let index = (y * width + x) as usize;
+                let [red, green, blue] = colors[index].map(|component| component.clamp(0., 255.));
+                let chosen = nearest(
+                    palette,
+                    to_lab(Srgb::new(
+                        red.round() as u8,
+                        green.round() as u8,
+                        blue.round() as u8,
+                    )),
+                );
+                self.set(pixel, chosen);
+
+                let target = self.palette[chosen];
+                let error = [
+                    red - target.red as f32,
+                    green - target.green as f32,
+                    blue - target.blue as f32,
+                ];
+                let mut spread = |dx: i32, dy: u32, weight: f32| {
+                    let (x, y) = (x as i32 + dx, y + dy);
+                    if x >= 0
+                        && x < width as i32
+                        && y < height
+                        && image.get_pixel(x as u32, y)[3] > 0
+                    {
+                        let neighbor = &mut colors[(y * width + x as u32) as usize];
+                        for (component, error) in neighbor.iter_mut().zip(&error) {
+                            *component += error * weight;
+                        }
+                    }
+                };
+                spread(1, 0, 7. / 16.);
+                spread(-1, 1, 3. / 16.);
+                spread(0, 1, 5. / 16.);
+                spread(1, 1, 1. / 16.);
+            }
+        }
+    }
+
+    fn set(&self, pixel: &mut image::Rgba<u8>, index: usize) {
+        let color = self.palette[index];
+        pixel[0] = color.red;
+        pixel[1] = color.green;
+        pixel[2] = color.blue;
+    }
+}
+
+fn to_lab(color: Srgb<u8>) -> Lab {
+    Lab::from(color.into_format::<f32>().into_linear())
+}
+
+/// The index of the color in `palette` closest to `color`
+fn nearest(palette: &[Lab], color: Lab) -> usize {
+    let distance = |other: &Lab| {
+        (other.l - color.l).powi(2) + (other.a - color.a).powi(2) + (other.b - color.b).powi(2)
+    };
+    (0..palette.len())
+        .min_by(|&a, &b| {
+            distance(&palette[a])
+                .partial_cmp(&distance(&palette[b]))
+                .unwrap()
+        })
+        .unwrap()
+}
@@ -0,0 +1,106 @@
+use crate::{coloring::ElevationColor, coverage, planet::GenerationAlgorithm, types::Kilometers};
+use euclid::Length;
+
+/// Controls how the terrain's raw elevation is mapped onto the ElevationColors
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum ElevationRemap {
+    /// Elevations are used as generated. Because noise clusters around its middle, the
+    /// lowest and highest ElevationColors rarely appear.
+    #[default]
+    Linear,
+
+    /// Elevations are remapped so that every ElevationColor covers an equal share of
+    /// the surface
+    Equalized,
+
+    /// Elevations are remapped through a curve of `(input, output)` control points, with
+    /// straight lines between them. Both values range from 0.0 at the lowest
+    /// ElevationColor to 1.0 at the highest.
+    Curve(Vec<(f32, f32)>),
+}
+
+/// A piecewise-linear mapping from one elevation to another
+#[derive(Clone, Debug)]
+pub struct ElevationCurve {
+    /// The `(input, output)` control points, sorted by input
+    pub points: Vec<(Length<f32, Kilometers>, Length<f32, Kilometers>)>,
+}
+
+impl ElevationRemap {
+    /// Builds the curve that applies this remap to `elevations`. `samples` are raw
+    /// elevations from across the whole sphere, sorted from lowest to highest.
+    pub(crate) fn curve<Kind>(
+        &self,
+        elevations: &[ElevationColor<Kind>],
+        samples: &[Length<f32, Kilometers>],
+        algorithm: GenerationAlgorithm,
+    ) -> Option<ElevationCurve> {
+        let lowest = elevations.first()?.elevation;
+        let highest = elevations.last()?.elevation;
+
+        let points = match self {
+            ElevationRemap::Linear => return None,
+            ElevationRemap::Equalized => {
+                if samples.is_empty() {
+                    return None;
+                }
+
+                // Each ElevationColor gets an equal share of the samples, split evenly
+                // between the bands it colors. Shares are counted in halves so that the
+                // fractions divide exactly.
+                let bands = coverage::bands(elevations, algorithm);
+                let mut band_counts = vec![0; elevations.len()];
+                for (_, index) in &bands {
+                    band_counts[*index] += 1;
+                }
+                let total_halves = (elevations.len() * 2) as f32;
+                let mut halves = 0;
+                std::iter::once((coverage::quantile(samples, 0.), lowest))
+                    .chain(bands.iter().map(|(top, index)| {
+                        halves += 2 / band_counts[*index];
+                        let input = coverage::quantile(samples, halves as f32 / total_halves);
+                        (input, *top)
+                    }))
+                    .collect()
+            }
+            ElevationRemap::Curve(points) => {
+                let mut points = points
+                    .iter()
+                    .map(|(input, output)| {
+                        (lowest.lerp(highest, *input), lowest.lerp(highest, *output))
+                    })
+                    .collect::<Vec<_>>();
+                points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                points
+            }
+        };
+
+        if points.is_empty() {
+            None
+        } else {
+            Some(ElevationCurve { points })
+        }
+    }
+}
+
+impl ElevationCurve {
+    /// Maps `elevation` through the curve. Elevations outside of the control points
+    /// are clamped to the first or last output.
+    pub fn apply(&self, elevation: Length<f32, Kilometers>) -> Length<f32, Kilometers> {
+        let next = self.points.iter().position(|(input, _)| *input > elevation);
+        match next {
+            Some(0) => self.points[0].1,
+            Some(index) => {
+                let (start_input, start_output) = self.points[index - 1];
+                let (end_input, end_output) = self.points[index];
+                let progress = (elevation - start_input).get() / (end_input - start_input).get();
+                start_output.lerp(end_output, progress)
+            }
+            None => self.points.last().unwrap().1,
+        }
+    }
+}
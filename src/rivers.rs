@@ -0,0 +1,303 @@
+use crate::types::{Kilometers, LatLong, UnitSphere};
+use euclid::{Length, Vector3D};
+use palette::Srgb;
+use rand::Rng;
+
+/// Settings for generating rivers that flow from highlands down to liquid
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Rivers<Kind> {
+    /// The kind reported for surface covered by a river
+    pub kind: Kind,
+
+    /// The color rivers are rendered with
+    pub color: Srgb<f32>,
+
+    /// How many sources to attempt to place. Rivers that never reach liquid are discarded,
+    /// so fewer rivers may be generated.
+    pub count: u32,
+
+    /// The lowest elevation a river's source can be placed at
+    pub source_elevation: Length<f32, Kilometers>,
+
+    /// How wide a river is rendered
+    pub width: Length<f32, Kilometers>,
+
+    /// How far a river travels between points on its path
+    pub step: Length<f32, Kilometers>,
+
+    /// The most points a river's path can contain
+    pub max_steps: u32,
+}
+
+impl<Kind> Rivers<Kind> {
+    /// Creates river settings that render rivers as `kind` using the provided color
+    pub fn from_u8(kind: Kind, r: u8, g: u8, b: u8) -> Self {
+        Self {
+            kind,
+            color: Srgb::new(r, g, b).into_format(),
+            count: 30,
+            source_elevation: Kilometers::new(300.),
+            width: Kilometers::new(60.),
+            step: Kilometers::new(25.),
+            max_steps: 400,
+        }
+    }
+}
+
+/// A river's path, from its source to where it reaches liquid or joins another river
+#[derive(Clone, Debug)]
+pub struct River {
+    /// The points the river passes through, starting at its source
+    pub path: Vec<LatLong>,
+
+    points: Vec<Vector3D<f32, UnitSphere>>,
+}
+
+/// River segments sorted into a grid of latitude and longitude cells. Each cell lists
+/// every segment within `reach` of it, so finding the rivers near a point only checks
+/// the segments of the cell it falls in.
+#[derive(Clone, Debug)]
+pub(crate) struct RiverIndex {
+    /// How far from a segment a point can be found, in radians
+    reach: f32,
+    rows: usize,
+    columns: usize,
+    cells: Vec<Vec<Segment>>,
+}
+
+type Segment = (Vector3D<f32, UnitSphere>, Vector3D<f32, UnitSphere>);
+
+/// The size of a `RiverIndex` cell, in radians of latitude and longitude
+const INDEX_CELL_SIZE: f32 = 0.05;
+
+/// The number of directions checked when looking for the steepest way downhill
+const DIRECTIONS: u32 = 8;
+
+/// How many random points are tried for each requested source
+const SOURCE_ATTEMPTS: u32 = 20;
+
+/// How many steps in a row a river can climb while trying to escape a basin
+const MAX_CLIMB: u32 = 10;
+
+impl<Kind> Rivers<Kind> {
+    /// Traces rivers over a sphere of `planet_radius`. `elevation_at` returns the elevation
+    /// of a point on the unit sphere, and `is_liquid` returns whether an elevation is liquid.
+    pub fn trace<R, E, L>(
+        &self,
+        rng: &mut R,
+        planet_radius: Length<f32, Kilometers>,
+        elevation_at: E,
+        is_liquid: L,
+    ) -> Vec<River>
+    where
+        R: Rng,
+        E: Fn(Vector3D<f32, UnitSphere>) -> Length<f32, Kilometers>,
+        L: Fn(Length<f32, Kilometers>) -> bool,
+    {
+        let step = self.step.get() / planet_radius.get();
+        let mut rivers = Vec::<River>::new();
+        let mut index = RiverIndex::new(step, &[]);
+
+        let mut attempts = 0;
+        let mut sources = 0;
+        while sources < self.count && attempts < self.count * SOURCE_ATTEMPTS {
+            attempts += 1;
+
+            // Uniformly distributed over the sphere's surface
+            let y = rng.gen_range(-1.0f32..1.);
+            let longitude = rng.gen_range(0.0f32..std::f32::consts::TAU);
+            let ring = (1. - y * y).sqrt();
+            let source = Vector3D::new(ring * longitude.sin(), y, ring * longitude.cos());
+
+            let source_elevation = elevation_at(source);
+            if source_elevation < self.source_elevation || is_liquid(source_elevation) {
+                continue;
+            }
+            sources += 1;
+
+            let mut points = vec![source];
+            let mut current = source;
+            let mut current_elevation = source_elevation;
+            let mut climbing = 0;
+            let mut finished = false;
+            while points.len() < self.max_steps as usize {
+                let (next, next_elevation) =
+                    match steepest_descent(current, step, &points, &elevation_at) {
+                        Some(next) => next,
+                        None => break,
+                    };
+                if next_elevation >= current_elevation {
+                    // Try to spill over the edge of a shallow basin, but give up on deep ones
+                    climbing += 1;
+                    if climbing > MAX_CLIMB {
+                        break;
+                    }
+                } else {
+                    climbing = 0;
+                }
+
+                points.push(next);
+                if is_liquid(next_elevation) || index.is_near(next) {
+                    finished = true;
+                    break;
+                }
+
+                current = next;
+                current_elevation = next_elevation;
+            }
+
+            if finished && points.len() > 1 {
+                let river = River::new(points);
+                index.insert(&river);
+                rivers.push(river);
+            }
+        }
+
+        rivers
+    }
+}
+
+/// Returns the lowest point `step` radians away from `point` that doesn't double back
+/// onto `visited`, and its elevation
+fn steepest_descent<E: Fn(Vector3D<f32, UnitSphere>) -> Length<f32, Kilometers>>(
+    point: Vector3D<f32, UnitSphere>,
+    step: f32,
+    visited: &[Vector3D<f32, UnitSphere>],
+    elevation_at: &E,
+) -> Option<(Vector3D<f32, UnitSphere>, Length<f32, Kilometers>)> {
+    // Build two tangent vectors to walk along the surface in every direction
+    let reference = if point.y.abs() < 0.9 {
+        Vector3D::new(0., 1., 0.)
+    } else {
+        Vector3D::new(1., 0., 0.)
+    };
+    let east = point.cross(reference).normalize();
+    let north = point.cross(east);
+
+    (0..DIRECTIONS)
+        .map(|direction| {
+            let angle = direction as f32 / DIRECTIONS as f32 * std::f32::consts::TAU;
+            let tangent = east * angle.cos() + north * angle.sin();
+            (point * step.cos() + tangent * step.sin()).normalize()
+        })
+        .filter(|candidate| {
+            visited
+                .iter()
+                .all(|visited| (*visited - *candidate).length() > step * 0.9)
+        })
+        .map(|candidate| (candidate, elevation_at(candidate)))
+        .fold(
+            None,
+            |lowest: Option<(_, Length<f32, Kilometers>)>, candidate| match lowest {
+                Some(lowest) if lowest.1 <= candidate.1 => Some(lowest),
+                _ => Some(candidate),
+            },
+        )
+}
+
+impl River {
+    fn new(points: Vec<Vector3D<f32, UnitSphere>>) -> Self {
+        Self {
+            path: points
+                .iter()
+                .map(|point| LatLong::from_surface_point(*point))
+                .collect(),
+            points,
+        }
+    }
+}
+
+impl RiverIndex {
+    /// Indexes `rivers` for finding the points within `reach` radians of them
+    pub(crate) fn new(reach: f32, rivers: &[River]) -> Self {
+        let rows = (std::f32::consts::PI / INDEX_CELL_SIZE).ceil() as usize;
+        let columns = (std::f32::consts::TAU / INDEX_CELL_SIZE).ceil() as usize;
+        let mut index = Self {
+            reach,
+            rows,
+            columns,
+            cells: vec![Vec::new(); rows * columns],
+        };
+        for river in rivers {
+            index.insert(river);
+        }
+        index
+    }
+
+    /// Adds the segments of `river` to every cell within `reach` of them
+    pub(crate) fn insert(&mut self, river: &River) {
+        for segment in river.points.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let middle = (start + end).try_normalize().unwrap_or(start);
+            // Every point near the segment lies within this angle of its middle. Doubling
+            // the reach covers the difference between straight line and surface distances.
+            let radius = start.dot(middle).clamp(-1., 1.).acos() + self.reach * 2.;
+            let center = LatLong::from_surface_point(middle);
+            let latitude = center.latitude.radians;
+
+            let first_row = self.row(latitude - radius);
+            let last_row = self.row(latitude + radius);
+            let smallest_ring = (latitude.abs() + radius)
+                .min(std::f32::consts::FRAC_PI_2)
+                .cos();
+            let columns = if radius.sin() >= smallest_ring {
+                // The area around the segment reaches a pole, and spans every longitude
+                0..self.columns as i64
+            } else {
+                let spread = (radius.sin() / smallest_ring).asin();
+                let longitude = center.longitude.radians;
+                self.column(longitude - spread)..self.column(longitude + spread) + 1
+            };
+
+            for row in first_row..=last_row {
+                for column in columns.clone().take(self.columns) {
+                    let cell = row * self.columns + column.rem_euclid(self.columns as i64) as usize;
+                    self.cells[cell].push((start, end));
+                }
+            }
+        }
+    }
+
+    /// Returns true if a river passes within `reach` of `point`
+    pub(crate) fn is_near(&self, point: Vector3D<f32, UnitSphere>) -> bool {
+        let position = LatLong::from_surface_point(point);
+        let cell = self.row(position.latitude.radians) * self.columns
+            + self
+                .column(position.longitude.radians)
+                .rem_euclid(self.columns as i64) as usize;
+        self.cells[cell]
+            .iter()
+            .any(|(start, end)| segment_distance(point, *start, *end) <= self.reach)
+    }
+
+    fn row(&self, latitude: f32) -> usize {
+        let row = ((latitude + std::f32::consts::FRAC_PI_2) / INDEX_CELL_SIZE).floor();
+        (row.max(0.) as usize).min(self.rows - 1)
+    }
+
+    /// The column of `longitude`, before wrapping around the grid
+    fn column(&self, longitude: f32) -> i64 {
+        ((longitude + std::f32::consts::PI) / INDEX_CELL_SIZE).floor() as i64
+    }
+}
+
+/// The approximate angular distance, in radians, from `point` to the segment between
+/// `start` and `end`
+fn segment_distance(
+    point: Vector3D<f32, UnitSphere>,
+    start: Vector3D<f32, UnitSphere>,
+    end: Vector3D<f32, UnitSphere>,
+) -> f32 {
+    let along = end - start;
+    let length = along.square_length();
+    let t = if length > 0. {
+        ((point - start).dot(along) / length).clamp(0., 1.)
+    } else {
+        0.
+    };
+    (start + along * t - point).length()
+}
@@ -0,0 +1,50 @@
+use crate::types::UnitSphere;
+use euclid::Vector3D;
+
+/// Settings for an irregular small body, such as an asteroid or a captured moon. Instead of
+/// a sphere, the surface is an ellipsoid displaced by noise and dented by craters.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Shape {
+    /// How much longer the body's long axis is than its other two. The planet's `radius`
+    /// is the radius of the short axes.
+    pub elongation: f32,
+
+    /// How far the noise can push the surface in or out, as a fraction of the radius
+    pub roughness: f32,
+
+    /// How many lumps span the body. Larger values produce a bumpier silhouette.
+    pub lumpiness: f32,
+
+    /// How strongly craters dent the surface. 1.0 carves them at their true depth.
+    pub crater_relief: f32,
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Self {
+            elongation: 1.6,
+            roughness: 0.25,
+            lumpiness: 1.2,
+            crater_relief: 1.,
+        }
+    }
+}
+
+impl Shape {
+    /// The farthest the surface can reach from the center, relative to the radius
+    pub fn max_extent(&self) -> f32 {
+        self.elongation.max(1.) * (1. + self.roughness.abs())
+    }
+
+    /// The radius of the undisplaced ellipsoid towards `direction`, relative to the radius
+    pub(crate) fn ellipsoid_radius(&self, direction: Vector3D<f32, UnitSphere>) -> f32 {
+        let elongation = self.elongation.max(1.);
+        let x = direction.x / elongation;
+        1. / (x * x + direction.y * direction.y + direction.z * direction.z).sqrt()
+    }
+}
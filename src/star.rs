@@ -0,0 +1,122 @@
+use crate::{planet::Light, types::Kilometers};
+use euclid::Length;
+use palette::Srgb;
+
+/// The effective surface temperature of our sun, in Kelvin
+pub const SOLAR_TEMPERATURE: f32 = 5772.;
+
+/// The radius of our sun
+pub const SOLAR_RADIUS: f32 = 696_340.;
+
+/// Stellar parameters used to render a `Planet` as a star instead of a lit, rocky body
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Star {
+    /// The effective surface temperature, in Kelvin. This determines the color of the star.
+    pub temperature: f32,
+
+    /// How strongly the edge of the disc darkens compared to its center. 0.0 disables
+    /// limb darkening, and 1.0 darkens the edge completely.
+    pub limb_darkening: f32,
+
+    /// How many granulation cells span the radius of the star
+    pub granulation: f32,
+
+    /// How much the granulation changes the brightness of the surface
+    pub granulation_contrast: f32,
+
+    /// How far the corona extends past the edge of the disc, as a fraction of the radius
+    pub corona: f32,
+
+    /// The opacity of the corona where it meets the edge of the disc
+    pub corona_intensity: f32,
+}
+
+impl Default for Star {
+    fn default() -> Self {
+        Self::with_temperature(SOLAR_TEMPERATURE)
+    }
+}
+
+impl Star {
+    /// A star with the appearance of our sun, at the provided temperature
+    pub fn with_temperature(temperature: f32) -> Self {
+        Self {
+            temperature,
+            limb_darkening: 0.6,
+            granulation: 40.,
+            granulation_contrast: 0.15,
+            corona: 0.25,
+            corona_intensity: 0.6,
+        }
+    }
+
+    /// The color of a black body at this star's temperature. This uses an approximation
+    /// that is accurate enough for picking colors between 1,000K and 40,000K.
+    pub fn color(&self) -> Srgb<f32> {
+        let temperature = self.temperature.clamp(1000., 40000.) / 100.;
+
+        let red = if temperature <= 66. {
+            255.
+        } else {
+            329.698_73 * (temperature - 60.).powf(-0.133_204_76)
+        };
+
+        let green = if temperature <= 66. {
+            99.470_8 * temperature.ln() - 161.119_57
+        } else {
+            288.122_17 * (temperature - 60.).powf(-0.075_514_85)
+        };
+
+        let blue = if temperature >= 66. {
+            255.
+        } else if temperature <= 19. {
+            0.
+        } else {
+            138.517_73 * (temperature - 10.).ln() - 305.044_8
+        };
+
+        Srgb::new(
+            red.clamp(0., 255.) / 255.,
+            green.clamp(0., 255.) / 255.,
+            blue.clamp(0., 255.) / 255.,
+        )
+    }
+
+    /// The light this star casts on the bodies orbiting it. The intensity is the luminosity
+    /// relative to our sun, calculated from the temperature and `radius` of the star.
+    pub fn light(&self, radius: Length<f32, Kilometers>) -> Light {
+        let relative_radius = radius.get() / SOLAR_RADIUS;
+        let relative_temperature = self.temperature / SOLAR_TEMPERATURE;
+        Light {
+            color: self.color(),
+            sols: relative_radius.powi(2) * relative_temperature.powi(4),
+        }
+    }
+
+    /// The fraction of the rendered image's radius that is occupied by the disc of the star
+    pub(crate) fn disc_scale(&self) -> f32 {
+        1. / (1. + self.corona.max(0.))
+    }
+
+    /// How bright the disc is at `distance` from the center, where 1.0 is the edge of the disc
+    pub(crate) fn limb_brightness(&self, distance: f32) -> f32 {
+        let mu = (1. - distance.min(1.).powi(2)).sqrt();
+        1. - self.limb_darkening * (1. - mu)
+    }
+
+    /// The opacity of the corona at `distance` from the center, where 1.0 is the edge of the
+    /// disc. `streamer` is a value between -1.0 and 1.0 that varies around the disc.
+    pub(crate) fn corona_opacity(&self, distance: f32, streamer: f32) -> f32 {
+        if self.corona <= 0. || distance < 1. {
+            return 0.;
+        }
+
+        let falloff = 1. - ((distance - 1.) / self.corona).min(1.);
+        self.corona_intensity * falloff.powi(2) * (1. + streamer * 0.25)
+    }
+}
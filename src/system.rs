@@ -0,0 +1,210 @@
+use crate::{
+    coloring::{DynamicKind, Palette, Sunlike},
+    orbit::Orbit,
+    planet::{Light, Planet},
+    star::{Star, SOLAR_RADIUS, SOLAR_TEMPERATURE},
+    types::Kilometers,
+};
+use euclid::{Angle, Length, Point2D};
+use image::RgbaImage;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::time::Duration;
+
+/// The mean distance between the Earth and our sun
+pub const ASTRONOMICAL_UNIT: f32 = 149_597_870.;
+
+/// How long the Earth takes to orbit our sun
+const YEAR: Duration = Duration::from_secs(31_557_600);
+
+/// The dimmest light a body is drawn with in an overview, so that the farthest bodies
+/// still show a lit crescent
+const MINIMUM_OVERVIEW_SOLS: f32 = 0.6;
+
+/// A star and the bodies orbiting it
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct System {
+    /// The star at the center of the system
+    pub star: Planet<Sunlike>,
+
+    /// The bodies orbiting the star, ordered from nearest to farthest
+    pub bodies: Vec<SystemBody>,
+}
+
+/// A body orbiting the star of a `System`
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct SystemBody {
+    pub kind: BodyKind,
+    pub planet: Planet<DynamicKind>,
+}
+
+/// What a body orbiting a star is made of
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum BodyKind {
+    /// A world of rock and metal, which forms inside the frost line
+    Rocky,
+    /// A world of frozen volatiles, which forms far past the frost line
+    Ice,
+    /// A giant world of gas, which forms just past the frost line
+    GasGiant,
+}
+
+impl BodyKind {
+    /// The kind of body that forms at `distance` from a star, given the star's frost line
+    pub fn at_distance(distance: f32, frost_line: f32) -> Self {
+        if distance < frost_line {
+            BodyKind::Rocky
+        } else if distance < frost_line * 4. {
+            BodyKind::GasGiant
+        } else {
+            BodyKind::Ice
+        }
+    }
+}
+
+impl System {
+    /// Generates a star and between three and eight bodies orbiting it. The bodies are
+    /// spaced out geometrically, and their kinds and palettes follow their distance from
+    /// the star. The same `seed` always generates the same system.
+    pub fn generate(seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        // Cool stars are far more common than hot ones
+        let temperature = 3_000. + 6_000. * rng.gen::<f32>().powi(2);
+        let relative_temperature = temperature / SOLAR_TEMPERATURE;
+        let radius = SOLAR_RADIUS * relative_temperature.powf(1.5) * rng.gen_range(0.8f32..1.2);
+        let star = Planet::new_star(
+            rng.gen(),
+            Kilometers::new(radius),
+            Star::with_temperature(temperature),
+        );
+        let luminosity = star.light().unwrap().sols;
+        let mass = luminosity.powf(0.25);
+
+        // Distances are in astronomical units until the bodies are created
+        let habitable_zone = luminosity.sqrt();
+        let frost_line = 2.7 * habitable_zone;
+        let count = rng.gen_range(3..=8);
+        let mut distance = habitable_zone * rng.gen_range(0.3f32..0.5);
+        let mut bodies = Vec::with_capacity(count);
+        for _ in 0..count {
+            let kind = BodyKind::at_distance(distance, frost_line);
+            let palette = match kind {
+                BodyKind::Rocky if distance < habitable_zone * 0.75 => {
+                    ["venus", "io", "mars", "moon"][rng.gen_range(0..4)]
+                }
+                BodyKind::Rocky if distance < habitable_zone * 1.5 => {
+                    ["earthlike", "ocean", "jungle", "mars"][rng.gen_range(0..4)]
+                }
+                BodyKind::Rocky => ["mars", "moon"][rng.gen_range(0..2)],
+                BodyKind::GasGiant => "jupiter",
+                BodyKind::Ice => "europa",
+            };
+            let radius = match kind {
+                BodyKind::Rocky => rng.gen_range(2_000f32..8_000.),
+                BodyKind::GasGiant => rng.gen_range(24_000f32..72_000.),
+                BodyKind::Ice => rng.gen_range(1_500f32..26_000.),
+            };
+
+            let period = YEAR.mul_f32((distance.powi(3) / mass).sqrt());
+            let orbit = Orbit {
+                eccentricity: rng.gen_range(0f32..0.1),
+                argument_of_periapsis: Angle::radians(rng.gen_range(0f32..std::f32::consts::TAU)),
+                ..Orbit::circular(
+                    Kilometers::new(distance * ASTRONOMICAL_UNIT),
+                    Angle::radians(rng.gen_range(0f32..std::f32::consts::TAU)),
+                    period,
+                )
+            };
+            let mut planet = Palette::preset(palette)
+                .expect("built-in preset")
+                .planet(
+                    rng.gen(),
+                    orbit.position_at(Duration::default()),
+                    Kilometers::new(radius),
+                )
+                .expect("built-in presets are valid");
+            planet.orbit = Some(orbit);
+            planet.rotation_period = Some(Duration::from_secs(rng.gen_range(8..60) * 60 * 60));
+            bodies.push(SystemBody { kind, planet });
+
+            distance *= rng.gen_range(1.4f32..2.1);
+        }
+
+        Self { star, bodies }
+    }
+
+    /// The light cast by the star at the center of the system
+    pub fn light(&self) -> Light {
+        self.star.light().unwrap_or_default()
+    }
+
+    /// Renders the star and every body in a row, from nearest to farthest, as they are at
+    /// `time`. The star is `pixels` tall, and the bodies are scaled by the square root of
+    /// their radius so that the smallest ones are still visible. Each body is lit by the
+    /// starlight that reaches its distance.
+    pub fn render_overview(&self, pixels: u32, time: Duration) -> RgbaImage {
+        let spacing = (pixels / 8).max(1);
+        let largest = self
+            .bodies
+            .iter()
+            .map(|body| body.planet.radius.get())
+            .fold(0., f32::max);
+
+        let light = self.light();
+        let mut images = vec![self.star.generate(pixels, &None).image];
+        for body in &self.bodies {
+            // Place every body to the right of the star, so that it's lit from the left
+            let mut planet = body.planet.at_time(time);
+            let distance = planet.origin.to_vector().length();
+            planet.origin = Point2D::new(distance, 0.);
+            let scale = (planet.radius.get() / largest).sqrt() * 0.75;
+            // Large enough to see, but never taller than the star
+            let size = ((pixels as f32 * scale) as u32).max(4).min(pixels);
+            // Starlight falls off with the inverse square of the distance in AUs, so a body
+            // in the habitable zone is lit like the Earth whatever the star
+            let relative_distance = distance / ASTRONOMICAL_UNIT;
+            let light = Light {
+                sols: (light.sols / relative_distance.powi(2)).max(MINIMUM_OVERVIEW_SOLS),
+                ..light
+            };
+            images.push(planet.generate(size, &Some(light)).image);
+        }
+
+        let width = images
+            .iter()
+            .map(|image| image.width() + spacing)
+            .sum::<u32>()
+            + spacing;
+        let mut overview = RgbaImage::new(width, pixels + spacing * 2);
+        let mut x = spacing;
+        for image in images {
+            let y = spacing + (pixels - image.height()) / 2;
+            image::imageops::overlay(&mut overview, &image, x, y);
+            x += image.width() + spacing;
+        }
+        overview
+    }
+}
+
+impl SystemBody {
+    /// The body's mean distance from the star
+    pub fn distance(&self) -> Length<f32, Kilometers> {
+        self.planet
+            .orbit
+            .map(|orbit| orbit.semi_major_axis)
+            .unwrap_or_else(|| Length::new(self.planet.origin.to_vector().length()))
+    }
+}
@@ -0,0 +1,175 @@
+use crate::types::{Kilometers, UnitSphere};
+use euclid::{Length, Vector3D};
+use rand::Rng;
+
+/// Settings for laying out continents with a simulation of tectonic plates. Each plate
+/// is a Voronoi cell on the sphere that rotates around its own axis. Mountain ranges rise
+/// where plates collide, and rifts open where they pull apart.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serialization", serde(default))]
+pub struct Tectonics {
+    /// How many plates cover the sphere
+    pub plates: u32,
+
+    /// The fraction of plates that carry continents, from 0.0 to 1.0. Values outside of
+    /// the range are clamped, and NaN is treated as 0.0.
+    pub continental_fraction: f32,
+
+    /// The elevation of the interior of a continental plate
+    pub continental_elevation: Length<f32, Kilometers>,
+
+    /// The elevation of the interior of an oceanic plate
+    pub oceanic_elevation: Length<f32, Kilometers>,
+
+    /// How high mountains rise where plates collide head on
+    pub mountain_height: Length<f32, Kilometers>,
+
+    /// How deep rifts sink where plates pull directly apart
+    pub rift_depth: Length<f32, Kilometers>,
+
+    /// How far the effects of a boundary reach into the plates on either side
+    pub boundary_width: Length<f32, Kilometers>,
+
+    /// How much the plates contribute to the final elevation. 0.0 uses only the terrain's
+    /// noise, and 1.0 uses only the plates.
+    pub blend: f32,
+}
+
+impl Default for Tectonics {
+    fn default() -> Self {
+        Self {
+            plates: 12,
+            continental_fraction: 0.35,
+            continental_elevation: Kilometers::new(400.),
+            oceanic_elevation: Kilometers::new(-1200.),
+            mountain_height: Kilometers::new(2000.),
+            rift_depth: Kilometers::new(800.),
+            boundary_width: Kilometers::new(800.),
+            blend: 0.6,
+        }
+    }
+}
+
+/// A single tectonic plate
+#[derive(Clone, Copy, Debug)]
+pub struct Plate {
+    /// The point on the unit sphere that the plate's Voronoi cell is built around
+    pub center: Vector3D<f32, UnitSphere>,
+
+    /// The axis the plate rotates around, scaled by its angular speed
+    pub rotation: Vector3D<f32, UnitSphere>,
+
+    /// Whether the plate carries a continent
+    pub continental: bool,
+}
+
+impl Plate {
+    /// The velocity of the surface of the plate at `surface_point`
+    fn velocity_at(&self, surface_point: Vector3D<f32, UnitSphere>) -> Vector3D<f32, UnitSphere> {
+        self.rotation.cross(surface_point)
+    }
+}
+
+fn random_surface_point<R: Rng>(rng: &mut R) -> Vector3D<f32, UnitSphere> {
+    let y = rng.gen_range(-1.0f32..1.);
+    let longitude = rng.gen_range(0.0f32..std::f32::consts::TAU);
+    let ring = (1. - y * y).sqrt();
+    Vector3D::new(ring * longitude.sin(), y, ring * longitude.cos())
+}
+
+impl Tectonics {
+    /// Randomly places the plates on the sphere and sets them in motion
+    pub fn place<R: Rng>(&self, rng: &mut R) -> Vec<Plate> {
+        // A fraction that isn't a number carries no continents
+        let continental_fraction = if self.continental_fraction.is_nan() {
+            0.
+        } else {
+            self.continental_fraction.clamp(0., 1.)
+        };
+        (0..self.plates.max(2))
+            .map(|_| Plate {
+                center: random_surface_point(rng),
+                rotation: random_surface_point(rng) * rng.gen_range(0.2f32..1.),
+                continental: rng.gen_bool(continental_fraction as f64),
+            })
+            .collect()
+    }
+
+    fn base_elevation(&self, plate: &Plate) -> f32 {
+        if plate.continental {
+            self.continental_elevation.get()
+        } else {
+            self.oceanic_elevation.get()
+        }
+    }
+
+    /// The elevation `plates` produce at `surface_point` on a sphere of `planet_radius`
+    pub fn elevation_at(
+        &self,
+        plates: &[Plate],
+        surface_point: Vector3D<f32, UnitSphere>,
+        planet_radius: Length<f32, Kilometers>,
+    ) -> Length<f32, Kilometers> {
+        // Find the plate the point belongs to, and the neighboring plate it is closest to
+        let mut nearest = (f32::MIN, 0);
+        let mut second = (f32::MIN, 0);
+        for (index, plate) in plates.iter().enumerate() {
+            let closeness = surface_point.dot(plate.center);
+            if closeness > nearest.0 {
+                second = nearest;
+                nearest = (closeness, index);
+            } else if closeness > second.0 {
+                second = (closeness, index);
+            }
+        }
+        let plate = &plates[nearest.1];
+        let neighbor = &plates[second.1];
+
+        // The boundary is the great circle halfway between the two plates' centers
+        let separation = plate.center - neighbor.center;
+        let boundary_distance = if separation.length() > f32::EPSILON {
+            (surface_point.dot(separation) / separation.length())
+                .clamp(-1., 1.)
+                .asin()
+                * planet_radius.get()
+        } else {
+            0.
+        };
+        let width = self.boundary_width.get().max(f32::EPSILON);
+        let proximity = (-(boundary_distance / width).powi(2)).exp();
+
+        // Plates blend into each other at the boundary instead of forming a cliff
+        let edge = (boundary_distance / width).min(1.);
+        let base = self.base_elevation(neighbor)
+            + (self.base_elevation(plate) - self.base_elevation(neighbor)) * (0.5 + edge * 0.5);
+
+        // Positive when the plates move towards each other across the boundary
+        let towards_neighbor = -separation - surface_point * surface_point.dot(-separation);
+        let convergence = if towards_neighbor.length() > f32::EPSILON {
+            let towards_neighbor = towards_neighbor.normalize();
+            let relative_velocity =
+                plate.velocity_at(surface_point) - neighbor.velocity_at(surface_point);
+            // Rotation speeds are at most 1.0, so plates can't approach faster than 2.0
+            (relative_velocity.dot(towards_neighbor) / 2.).clamp(-1., 1.)
+        } else {
+            0.
+        };
+
+        let boundary = if convergence > 0. {
+            if !plate.continental && neighbor.continental {
+                // Oceanic plates sink beneath continents, forming a trench
+                -self.rift_depth.get() * convergence * proximity
+            } else {
+                self.mountain_height.get() * convergence * proximity
+            }
+        } else {
+            self.rift_depth.get() * convergence * proximity
+        };
+
+        Kilometers::new(base + boundary)
+    }
+}
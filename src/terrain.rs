@@ -1,15 +1,70 @@
 use crate::{
+    banding::Banding,
+    canvas::Canvas,
     coloring::ElevationColor,
-    planet::{GeneratedPlanet, Light, Planet},
-    types::{Kilometers, Pixels},
+    coverage::{self, Coverage},
+    craters::{Crater, Craters},
+    erosion::ElevationGrid,
+    planet::{
+        GeneratedPlanet, GenerationAlgorithm, Light, ParallelSafe, Planet, SurfaceDefinition,
+    },
+    remap::ElevationCurve,
+    rivers::{River, RiverIndex, Rivers},
+    shape::Shape,
+    star::Star,
+    tectonics::{Plate, Tectonics},
+    types::{Kilometers, Pixels, UnitSphere},
 };
-use euclid::{Angle, Length, Point2D, Rotation2D, Vector2D};
+use euclid::{Angle, Length, Point2D, Rect, Rotation2D, Scale, Size2D, Vector2D, Vector3D};
+use image::{GenericImage, Rgba};
 use noise::{NoiseFn, OpenSimplex, Seedable};
 use palette::{Shade, Srgb};
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rand::{rngs::SmallRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use sorted_vec::partial::SortedVec;
 use std::{collections::HashMap, hash::Hash};
 
+/// How many layers of noise displace the surface of an irregular body
+const SHAPE_OCTAVES: u32 = 3;
+
+/// How many steps a ray takes through an irregular body's bounds looking for the surface
+const SHAPE_STEPS: u32 = 48;
+
+/// How many times the surface found by a ray is refined by bisection
+const SHAPE_REFINEMENTS: u32 = 8;
+
+/// The offset used to estimate the normal of an irregular body's surface
+const SHAPE_EPSILON: f32 = 0.002;
+
+/// How many rays are cast along each axis of a pixel on the edge of an irregular body
+const SHAPE_EDGE_SAMPLES: u32 = 4;
+
+/// Where a ray from the viewer meets the surface of an irregular body
+struct ShapeHit {
+    /// The point in view space, relative to the radius
+    point: Vector3D<f32, UnitSphere>,
+
+    /// The direction of the point from the center, in the planet's own frame
+    surface_point: Vector3D<f32, UnitSphere>,
+
+    /// The surface's normal in view space
+    normal: Vector3D<f32, UnitSphere>,
+}
+
+// The streams of random numbers the optional features draw from
+const CRATER_STREAM: u64 = 1;
+const TECTONICS_STREAM: u64 = 2;
+const EROSION_STREAM: u64 = 3;
+const RIVERS_STREAM: u64 = 4;
+
+/// The random number generator for an optional feature. Each feature draws from its own
+/// stream, so turning one on doesn't change the others.
+fn feature_rng(seed: u64, stream: u64) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(stream);
+    rng
+}
+
 /// A randomly generated elevation map
 pub struct Terrain<Kind> {
     /// Per kilometer of distance between another point, how much can the surface change?
@@ -18,65 +73,430 @@ pub struct Terrain<Kind> {
     /// The origin of the planet
     pub origin: Point2D<f32, Kilometers>,
 
+    /// Where the light shining on the planet comes from. Defaults to the star at the center
+    /// of the system.
+    pub light_source: Point2D<f32, Kilometers>,
+
     /// The radius of the planet
     pub radius: Length<f32, Kilometers>,
 
+    /// The version of the generation algorithm the terrain was generated with
+    pub algorithm: GenerationAlgorithm,
+
+    /// How far the planet has spun around its axis
+    pub rotation: Angle<f32>,
+
     /// A 2d spatial tree of points
     pub noise: OpenSimplex,
 
     /// A sorted collection of ElevationColors
     pub elevations: SortedVec<ElevationColor<Kind>>,
+
+    /// If present, the terrain is rendered as a star
+    pub star: Option<Star>,
+
+    /// If present, the noise is stretched into bands of latitude
+    pub banding: Option<Banding>,
+
+    /// If present, the terrain is rendered as an irregular small body instead of a sphere
+    pub shape: Option<Shape>,
+
+    /// The settings used to carve `craters`
+    pub cratering: Option<Craters>,
+
+    /// The impact craters carved into the surface, largest first
+    pub craters: Vec<Crater>,
+
+    /// The settings used to shape the surface with `plates`
+    pub tectonics: Option<Tectonics>,
+
+    /// The tectonic plates covering the surface
+    pub plates: Vec<Plate>,
+
+    /// If present, the eroded elevation that is sampled instead of the noise and craters
+    pub elevation_grid: Option<ElevationGrid>,
+
+    /// If present, every elevation is mapped through this curve before the offset is added
+    pub elevation_curve: Option<ElevationCurve>,
+
+    /// Added to every elevation so that the surface meets its `Coverage` target
+    pub elevation_offset: Length<f32, Kilometers>,
+
+    /// The settings used to trace and render `rivers`
+    pub river_settings: Option<Rivers<Kind>>,
+
+    /// The rivers flowing across the surface
+    pub rivers: Vec<River>,
+
+    /// `rivers`, indexed for finding the points they cover
+    river_index: Option<RiverIndex>,
 }
 
 impl<Kind> Terrain<Kind>
 where
-    Kind: Clone + Hash + Eq,
+    Kind: SurfaceDefinition + Clone + Hash + Eq,
 {
     /// Randomly generate a new terrain for the Planet provided
     pub fn generate(planet: &Planet<Kind>) -> Self {
-        let mut rng = SmallRng::seed_from_u64(planet.seed);
+        let mut rng: Box<dyn RngCore> = match planet.algorithm {
+            GenerationAlgorithm::V0 => Box::new(SmallRng::seed_from_u64(planet.seed)),
+            GenerationAlgorithm::V1 => Box::new(ChaCha8Rng::seed_from_u64(planet.seed)),
+        };
 
         // How much variation in elevation do we want to allow per kilometer of distance?
         let surface_chaos = rng.gen_range(1.0f32..planet.max_chaos.max(1.));
         let terrain_seed = rng.gen();
+        let craters = planet
+            .craters
+            .map(|craters| {
+                craters.place(&mut feature_rng(planet.seed, CRATER_STREAM), planet.radius)
+            })
+            .unwrap_or_default();
+        let plates = planet
+            .tectonics
+            .map(|tectonics| tectonics.place(&mut feature_rng(planet.seed, TECTONICS_STREAM)))
+            .unwrap_or_default();
 
-        Terrain {
+        let mut terrain = Terrain {
             origin: planet.origin,
+            light_source: Point2D::zero(),
             radius: planet.radius,
+            algorithm: planet.algorithm,
+            rotation: planet.rotation,
             noise: OpenSimplex::new().set_seed(terrain_seed),
             surface_chaos,
             elevations: planet.colors.clone(),
+            star: planet.star,
+            banding: planet.banding,
+            shape: planet.shape,
+            cratering: planet.craters,
+            craters,
+            tectonics: planet.tectonics,
+            plates,
+            elevation_grid: None,
+            elevation_curve: None,
+            elevation_offset: Length::default(),
+            river_settings: planet.rivers.clone(),
+            rivers: Vec::new(),
+            river_index: None,
+        };
+
+        if let Some(erosion) = &planet.erosion {
+            let grid = erosion.erode(
+                &mut feature_rng(planet.seed, EROSION_STREAM),
+                |surface_point| terrain.uneroded_elevation_at(surface_point),
+            );
+            terrain.elevation_grid = Some(grid);
         }
+
+        terrain.elevation_curve = planet.remap.curve(
+            &terrain.elevations,
+            &terrain.sample_sphere(),
+            planet.algorithm,
+        );
+
+        if let Some(coverage) = &planet.coverage {
+            terrain.elevation_offset = terrain.solve_coverage(coverage);
+        }
+
+        if let Some(rivers) = &planet.rivers {
+            terrain.rivers = rivers.trace(
+                &mut feature_rng(planet.seed, RIVERS_STREAM),
+                planet.radius,
+                |surface_point| terrain.elevation_at(surface_point),
+                |elevation| terrain.closest_elevation(elevation).kind.is_liquid(),
+            );
+            let half_width = rivers.width.get() / 2. / planet.radius.get();
+            terrain.river_index = Some(RiverIndex::new(half_width, &terrain.rivers));
+        }
+
+        terrain
     }
 
-    /// For a given point on the surface, return what kind and what color the point is
-    pub fn extrapolate_point(
+    /// Projects a point on the visible disc of the planet onto the unit sphere. Without
+    /// rotation, the returned point's z axis points towards the viewer.
+    fn surface_point(&self, planet_point: Point2D<f32, Kilometers>) -> Vector3D<f32, UnitSphere> {
+        let normalized_point = planet_point.to_vector() / self.radius.get();
+        let depth = (1. - normalized_point.square_length()).max(0.).sqrt();
+        self.rotate(normalized_point.extend(depth).cast_unit())
+    }
+
+    /// Turns a point in view space, where z points towards the viewer, to the planet's
+    /// own frame
+    fn rotate(&self, point: Vector3D<f32, UnitSphere>) -> Vector3D<f32, UnitSphere> {
+        // Spinning the planet carries its surface from left to right
+        let (sin, cos) = self.rotation.sin_cos();
+        Vector3D::new(
+            point.x * cos - point.z * sin,
+            point.y,
+            point.z * cos + point.x * sin,
+        )
+    }
+
+    /// The distance from the center to the surface of an irregular body towards
+    /// `direction` in the planet's own frame, relative to the radius
+    fn shape_radius(&self, shape: &Shape, direction: Vector3D<f32, UnitSphere>) -> f32 {
+        // A few octaves of noise, offset so the lumps don't line up with the terrain
+        let mut lumps = 0.;
+        let mut amplitude = 1.;
+        let mut lump_point = direction * shape.lumpiness + Vector3D::new(0., 0., 91.);
+        for _ in 0..SHAPE_OCTAVES {
+            lumps += self.noise.get(lump_point.to_f64().to_array()) as f32 * amplitude;
+            amplitude *= 0.5;
+            lump_point *= 2.;
+        }
+        let mut radius = shape.ellipsoid_radius(direction) * (1. + shape.roughness * lumps);
+
+        if let Some(cratering) = &self.cratering {
+            for crater in &self.craters {
+                if direction.dot(crater.center)
+                    > Craters::influence_angle(crater, self.radius).cos()
+                {
+                    radius += cratering
+                        .elevation_change(crater, direction, self.radius)
+                        .get()
+                        * shape.crater_relief
+                        / self.radius.get();
+                }
+            }
+        }
+
+        radius.min(shape.max_extent())
+    }
+
+    /// How far `point` in view space is outside of the surface of an irregular body,
+    /// relative to the radius. Negative values are inside.
+    fn shape_distance(&self, shape: &Shape, point: Vector3D<f32, UnitSphere>) -> f32 {
+        let point = self.rotate(point);
+        let length = point.length();
+        if length <= f32::EPSILON {
+            return -1.;
+        }
+        length - self.shape_radius(shape, point / length)
+    }
+
+    /// Casts a ray from the viewer through `planet_point`, returning where it first meets
+    /// the surface of an irregular body
+    fn shape_hit(&self, shape: &Shape, planet_point: Point2D<f32, Kilometers>) -> Option<ShapeHit> {
+        let extent = shape.max_extent();
+        let normalized_point = (planet_point.to_vector() / self.radius.get()).cast_unit();
+        let depth_squared = extent * extent - normalized_point.square_length();
+        if depth_squared <= 0. {
+            return None;
+        }
+
+        let depth = depth_squared.sqrt();
+        let distance = |z: f32| self.shape_distance(shape, normalized_point.extend(z));
+        let step = depth * 2. / SHAPE_STEPS as f32;
+        let mut outside = depth;
+        let mut inside = (1..=SHAPE_STEPS)
+            .map(|index| depth - step * index as f32)
+            .find(|&z| {
+                let found = distance(z) < 0.;
+                if !found {
+                    outside = z;
+                }
+                found
+            })?;
+        for _ in 0..SHAPE_REFINEMENTS {
+            let middle = (outside + inside) / 2.;
+            if distance(middle) < 0. {
+                inside = middle;
+            } else {
+                outside = middle;
+            }
+        }
+
+        let point = normalized_point.extend(inside);
+        let gradient = |offset: Vector3D<f32, UnitSphere>| {
+            self.shape_distance(shape, point + offset) - self.shape_distance(shape, point - offset)
+        };
+        let normal = Vector3D::new(
+            gradient(Vector3D::new(SHAPE_EPSILON, 0., 0.)),
+            gradient(Vector3D::new(0., SHAPE_EPSILON, 0.)),
+            gradient(Vector3D::new(0., 0., SHAPE_EPSILON)),
+        )
+        .try_normalize()
+        .unwrap_or_else(|| point.normalize());
+
+        Some(ShapeHit {
+            point,
+            surface_point: self.rotate(point).normalize(),
+            normal,
+        })
+    }
+
+    /// Finds the surface of an irregular body seen through the pixel at `planet_point`,
+    /// along with how much of the pixel it covers. Pixels whose corners disagree with
+    /// their center about meeting the body are supersampled to soften the silhouette.
+    fn shape_coverage(
         &self,
+        shape: &Shape,
         planet_point: Point2D<f32, Kilometers>,
-        sun: &Option<Light>,
-    ) -> (Kind, Srgb<u8>) {
-        let normalized_point = planet_point.to_vector() / self.radius.get() * self.surface_chaos;
-        let noise = self.noise.get(normalized_point.to_f64().to_array()) as f32;
-        // Convert the -1.0..1.0 range of the noise to 0.0..1.0
-        let noise = (noise + 1.0) / 2.0;
-        let elevation_range =
-            self.elevations.first().unwrap().elevation..self.elevations.last().unwrap().elevation;
-        let elevation =
-            elevation_range.start + (elevation_range.end - elevation_range.start) * noise;
+        planet_scale: Scale<f32, Pixels, Kilometers>,
+    ) -> Option<(ShapeHit, u8)> {
+        let hit_at = |x: f32, y: f32| {
+            self.shape_hit(shape, planet_point + Vector2D::new(x, y) * planet_scale)
+        };
+        let center = self.shape_hit(shape, planet_point);
+        let corners = [(-0.5, -0.5), (0.5, -0.5), (-0.5, 0.5), (0.5, 0.5)];
+        if corners
+            .iter()
+            .all(|&(x, y)| hit_at(x, y).is_some() == center.is_some())
+        {
+            return center.map(|hit| (hit, 255));
+        }
+
+        let offset = |index: u32| (index as f32 + 0.5) / SHAPE_EDGE_SAMPLES as f32 - 0.5;
+        let mut hits = 0;
+        let mut first = None;
+        for row in 0..SHAPE_EDGE_SAMPLES {
+            for column in 0..SHAPE_EDGE_SAMPLES {
+                if let Some(hit) = hit_at(offset(column), offset(row)) {
+                    hits += 1;
+                    first.get_or_insert(hit);
+                }
+            }
+        }
+        let samples = SHAPE_EDGE_SAMPLES * SHAPE_EDGE_SAMPLES;
+        let alpha = (255 * hits + samples / 2) / samples;
+        center.or(first).map(|hit| (hit, alpha as u8))
+    }
+
+    /// Returns the elevations of points spread evenly across the whole sphere, sorted
+    /// from lowest to highest
+    fn sample_sphere(&self) -> Vec<Length<f32, Kilometers>> {
+        let mut samples = UnitSphere::evenly_spaced(coverage::SAMPLES)
+            .map(|surface_point| self.elevation_at(surface_point))
+            .collect::<Vec<_>>();
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        samples
+    }
+
+    /// Returns the elevation offset that meets `coverage`, measured over the whole sphere
+    fn solve_coverage(&self, coverage: &Coverage<Kind>) -> Length<f32, Kilometers> {
+        coverage
+            .solve_offset(&self.elevations, &self.sample_sphere(), self.algorithm)
+            .unwrap_or_default()
+    }
+
+    /// The elevation of the surface at `surface_point` on the unit sphere
+    pub fn elevation_at(
+        &self,
+        surface_point: Vector3D<f32, UnitSphere>,
+    ) -> Length<f32, Kilometers> {
+        let elevation = match &self.elevation_grid {
+            Some(grid) => grid.sample(surface_point),
+            None => self.uneroded_elevation_at(surface_point),
+        };
+        let elevation = match &self.elevation_curve {
+            Some(curve) => curve.apply(elevation),
+            None => elevation,
+        };
+
+        elevation + self.elevation_offset
+    }
+
+    /// The elevation of the surface at `surface_point` before erosion is applied
+    fn uneroded_elevation_at(
+        &self,
+        surface_point: Vector3D<f32, UnitSphere>,
+    ) -> Length<f32, Kilometers> {
+        let noise_point = match &self.banding {
+            Some(banding) => {
+                let swirl_point = surface_point * 3. + Vector3D::new(0., 53., 0.);
+                let swirl = self.noise.get(swirl_point.to_f64().to_array()) as f32;
+                banding.banded_point(surface_point, swirl)
+            }
+            None => surface_point,
+        } * self.surface_chaos;
+        let noise = match self.algorithm {
+            // The original algorithm sampled flat noise across the disc facing the viewer
+            GenerationAlgorithm::V0 => self.noise.get([noise_point.x as f64, noise_point.y as f64]),
+            GenerationAlgorithm::V1 => self.noise.get(noise_point.to_f64().to_array()),
+        } as f32;
+        let mut elevation = self.noise_to_elevation(noise);
+
+        if let Some(tectonics) = &self.tectonics {
+            // Warp the point so that plate boundaries aren't perfect great circles
+            let warp_point = surface_point * 2.;
+            let warp = Vector3D::<f32, UnitSphere>::new(
+                self.noise.get(
+                    (warp_point + Vector3D::new(17., 0., 0.))
+                        .to_f64()
+                        .to_array(),
+                ) as f32,
+                self.noise.get(
+                    (warp_point + Vector3D::new(0., 31., 0.))
+                        .to_f64()
+                        .to_array(),
+                ) as f32,
+                self.noise.get(
+                    (warp_point + Vector3D::new(0., 0., 47.))
+                        .to_f64()
+                        .to_array(),
+                ) as f32,
+            );
+            let plate_point = (surface_point + warp * 0.2).normalize();
+            let plate_elevation = tectonics.elevation_at(&self.plates, plate_point, self.radius);
+            elevation = elevation.lerp(plate_elevation, tectonics.blend);
+        }
+
+        if let Some(cratering) = &self.cratering {
+            for crater in &self.craters {
+                if surface_point.dot(crater.center)
+                    > Craters::influence_angle(crater, self.radius).cos()
+                {
+                    elevation += cratering.elevation_change(crater, surface_point, self.radius);
+                }
+            }
+        }
+
+        elevation
+    }
 
+    /// The ElevationColor that colors `surface_point` on the unit sphere, not counting
+    /// rivers
+    pub fn elevation_color_at(
+        &self,
+        surface_point: Vector3D<f32, UnitSphere>,
+    ) -> &ElevationColor<Kind> {
+        self.closest_elevation(self.elevation_at(surface_point))
+    }
+
+    /// Returns the river settings if a river flows through `surface_point`
+    fn river_at(&self, surface_point: Vector3D<f32, UnitSphere>) -> Option<&Rivers<Kind>> {
+        let settings = self.river_settings.as_ref()?;
+        if self.river_index.as_ref()?.is_near(surface_point) {
+            Some(settings)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ElevationColor that `elevation` is colored with. Since
+    /// `GenerationAlgorithm::V1`, this is the closest one.
+    fn closest_elevation(&self, elevation: Length<f32, Kilometers>) -> &ElevationColor<Kind> {
         let closest_elevation = match self
             .elevations
             .binary_search_by(|probe| probe.elevation.partial_cmp(&elevation).unwrap())
         {
             Ok(index) => index,
             Err(index) => {
-                // We didn't match, generate a random variation between these two elevations with probabilty from how close of a match it is
+                // We didn't match, pick one of the two neighboring elevations
                 if index == 0 {
                     index
+                } else if index == self.elevations.len() {
+                    index - 1
                 } else {
                     let delta_a = self.elevations[index].elevation - elevation;
                     let delta_b = elevation - self.elevations[index - 1].elevation;
-                    if delta_a > delta_b {
+                    // The original algorithm picked whichever was farther away
+                    let pick_upper = match self.algorithm {
+                        GenerationAlgorithm::V0 => delta_a > delta_b,
+                        GenerationAlgorithm::V1 => delta_a < delta_b,
+                    };
+                    if pick_upper {
                         index
                     } else {
                         index - 1
@@ -85,23 +505,93 @@ where
             }
         };
 
-        let terrain_kind = self.elevations[closest_elevation].kind.clone();
-        let terrain_color = self.elevations[closest_elevation].color.into_linear();
+        &self.elevations[closest_elevation]
+    }
+
+    /// Converts a noise value in the range of -1.0..1.0 to an elevation between the lowest
+    /// and highest ElevationColors
+    fn noise_to_elevation(&self, noise: f32) -> Length<f32, Kilometers> {
+        // Convert the -1.0..1.0 range of the noise to 0.0..1.0
+        let noise = (noise + 1.0) / 2.0;
+        let elevation_range =
+            self.elevations.first().unwrap().elevation..self.elevations.last().unwrap().elevation;
+        elevation_range.start + (elevation_range.end - elevation_range.start) * noise
+    }
+
+    /// The position of `planet_point` relative to the light source
+    fn space_point(&self, planet_point: Point2D<f32, Kilometers>) -> Point2D<f32, Kilometers> {
+        self.origin - self.light_source.to_vector() + planet_point.to_vector()
+    }
 
-        let space_point = self.origin + planet_point.to_vector();
+    /// For a given point on the surface, return what kind and what color the point is
+    pub fn extrapolate_point(
+        &self,
+        planet_point: Point2D<f32, Kilometers>,
+        sun: &Option<Light>,
+    ) -> (Kind, Srgb<u8>) {
+        let surface_point = self.surface_point(planet_point);
+
+        let space_point = self.space_point(planet_point);
         let angle_to_sun =
             Angle::radians(space_point.y.atan2(space_point.x)) + Angle::degrees(180.);
-        let distance_to_sun = space_point.distance_to(Default::default());
         let focus_point = Rotation2D::new(angle_to_sun)
             .transform_point(Point2D::from_lengths(self.radius, Default::default()));
         let distance_from_focus = planet_point.distance_to(focus_point);
+        let sphere_dimming = distance_from_focus / (self.radius.get() * 1.4);
+
+        self.shade(surface_point, space_point, sphere_dimming, sun)
+    }
+
+    /// For a point where a ray meets the surface of an irregular body, return what kind
+    /// and what color the point is. The lighting follows the surface's normal.
+    fn extrapolate_shaped_point(&self, hit: &ShapeHit, sun: &Option<Light>) -> (Kind, Srgb<u8>) {
+        let planet_point = Point2D::new(hit.point.x, hit.point.y) * self.radius.get();
+        let space_point = self.space_point(planet_point);
+        let to_sun = (-space_point.to_vector())
+            .try_normalize()
+            .unwrap_or_default();
+
+        // On a sphere, this is the same as the distance from the focus point that
+        // `extrapolate_point` uses
+        let facing = hit.normal.x * to_sun.x + hit.normal.y * to_sun.y;
+        let sphere_dimming = (2. - 2. * facing - hit.normal.z * hit.normal.z)
+            .max(0.)
+            .sqrt()
+            / 1.4;
+
+        self.shade(hit.surface_point, space_point, sphere_dimming, sun)
+    }
+
+    /// Colors `surface_point` and shades it by the light. `sphere_dimming` is how far the
+    /// point faces away from the sun, from 0.0 facing it to about 1.4 facing away.
+    fn shade(
+        &self,
+        surface_point: Vector3D<f32, UnitSphere>,
+        space_point: Point2D<f32, Kilometers>,
+        sphere_dimming: f32,
+        sun: &Option<Light>,
+    ) -> (Kind, Srgb<u8>) {
+        let elevation = self.elevation_color_at(surface_point);
+
+        let (terrain_kind, terrain_color) = match self.river_at(surface_point) {
+            Some(river) if !elevation.kind.is_liquid() => (river.kind.clone(), river.color),
+            _ => (elevation.kind.clone(), elevation.color),
+        };
+        let terrain_color = terrain_color.into_linear();
+
+        let distance_to_sun = space_point.distance_to(Default::default());
 
         // Shade based on the lighting
         let color = match sun {
             Some(sun) => {
                 let distance_dimming = 1.0 - 1. / distance_to_sun;
-                let sphere_dimming = distance_from_focus / (self.radius.get() * 1.4);
-                let sun_base_factor = sun.sols * distance_dimming * sphere_dimming;
+                let sun_base_factor = match self.algorithm {
+                    GenerationAlgorithm::V0 => sun.sols * distance_dimming * sphere_dimming,
+                    // Brighter light reaches further around the planet before it fades out
+                    GenerationAlgorithm::V1 => {
+                        distance_dimming * sphere_dimming / sun.sols.max(f32::MIN_POSITIVE)
+                    }
+                };
 
                 terrain_color
                     * sun
@@ -124,46 +614,290 @@ where
         )
     }
 
+    /// For a given point on the surface of a star, return what kind and what color the point is.
+    /// External lighting is ignored, as stars provide their own light.
+    pub fn extrapolate_star_point(
+        &self,
+        star: &Star,
+        planet_point: Point2D<f32, Kilometers>,
+    ) -> (Kind, Srgb<u8>) {
+        let surface_point = self.surface_point(planet_point);
+        let distance = (planet_point.to_vector() / self.radius.get())
+            .length()
+            .min(1.);
+
+        // Granulation is sampled on the sphere so that the cells foreshorten towards the limb
+        let granulation_point = surface_point * star.granulation;
+        let granulation = self.noise.get(granulation_point.to_f64().to_array()) as f32;
+        let elevation = self.closest_elevation(self.noise_to_elevation(granulation));
+
+        let brightness =
+            star.limb_brightness(distance) * (1. + granulation * star.granulation_contrast);
+        let color = star.color().into_linear() * brightness.max(0.);
+        let color = Srgb::from_linear(color);
+
+        (
+            elevation.kind.clone(),
+            Srgb::new(
+                (color.red.min(1.) * 255.0) as u8,
+                (color.green.min(1.) * 255.0) as u8,
+                (color.blue.min(1.) * 255.0) as u8,
+            ),
+        )
+    }
+
+    /// The color of a star's corona at `point`, which is `distance` from the center of the disc.
+    /// `distance` is relative to the radius of the disc.
+    fn corona_color(&self, star: &Star, point: Vector2D<f32, Pixels>, distance: f32) -> [u8; 4] {
+        let angle = point.y.atan2(point.x);
+        let streamer = self
+            .noise
+            .get([(angle.cos() * 3.) as f64, (angle.sin() * 3.) as f64]) as f32;
+        let opacity = star.corona_opacity(distance, streamer).clamp(0., 1.);
+        let color = star.color();
+
+        [
+            (color.red * 255.) as u8,
+            (color.green * 255.) as u8,
+            (color.blue * 255.) as u8,
+            (opacity * 255.) as u8,
+        ]
+    }
+
     /// Generates an image of `pixels` wide, and `pixels` tall. If a light is provided
     /// a shadow is simulated, and the colors are mixed with the light's color
-    pub fn generate_planet(self, pixels: u32, sun: &Option<Light>) -> GeneratedPlanet<Kind> {
-        let mut image = image::RgbaImage::new(pixels, pixels);
-        let radius = Length::<f32, Pixels>::new(pixels as f32 / 2.);
-        let planet_scale = self.radius / radius;
+    pub fn generate_planet(&self, pixels: u32, sun: &Option<Light>) -> GeneratedPlanet<Kind>
+    where
+        Kind: ParallelSafe,
+    {
+        self.generate_canvas(&Canvas::square(pixels), sun)
+    }
+
+    /// Generates an image the size of `canvas`, with the planet at the canvas's center
+    /// and diameter. The area around the planet is transparent, apart from a star's corona.
+    pub fn generate_canvas(&self, canvas: &Canvas, sun: &Option<Light>) -> GeneratedPlanet<Kind>
+    where
+        Kind: ParallelSafe,
+    {
+        let mut image = image::RgbaImage::new(canvas.width, canvas.height);
+        let stats = self.render_into(canvas, &mut image, Point2D::zero(), sun);
+        GeneratedPlanet {
+            image,
+            stats,
+            rivers: self.rivers.clone(),
+        }
+    }
+
+    /// Renders the whole of `canvas` into `target`, with the canvas's top left corner at
+    /// `offset`. Every pixel the canvas covers is overwritten, including the transparent
+    /// ones around the planet. Returns how many pixels of each kind were rendered.
+    ///
+    /// Panics if the canvas doesn't fit inside `target` at `offset`.
+    pub fn render_into<I>(
+        &self,
+        canvas: &Canvas,
+        target: &mut I,
+        offset: Point2D<u32, Pixels>,
+        sun: &Option<Light>,
+    ) -> HashMap<Kind, u32>
+    where
+        Kind: ParallelSafe,
+        I: GenericImage<Pixel = Rgba<u8>>,
+    {
+        let tile = Rect::new(Point2D::zero(), Size2D::new(canvas.width, canvas.height));
+        self.render_tile(canvas, tile, target, offset, sun)
+    }
+
+    /// Renders only the pixels of `canvas` inside `tile` into `target`, with the tile's top
+    /// left corner at `offset`. Rendering a large canvas one tile at a time produces the
+    /// same pixels as rendering it at once, while only needing a buffer the size of a tile.
+    /// Returns how many pixels of each kind were rendered.
+    ///
+    /// Panics if the tile isn't inside `canvas`, or doesn't fit inside `target` at `offset`.
+    pub fn render_tile<I>(
+        &self,
+        canvas: &Canvas,
+        tile: Rect<u32, Pixels>,
+        target: &mut I,
+        offset: Point2D<u32, Pixels>,
+        sun: &Option<Light>,
+    ) -> HashMap<Kind, u32>
+    where
+        Kind: ParallelSafe,
+        I: GenericImage<Pixel = Rgba<u8>>,
+    {
+        let fits = |start: u32, length: u32, limit: u32| {
+            matches!(start.checked_add(length), Some(end) if end <= limit)
+        };
+        assert!(
+            fits(tile.origin.x, tile.width(), canvas.width)
+                && fits(tile.origin.y, tile.height(), canvas.height),
+            "the tile {:?} isn't inside the {}x{} canvas",
+            tile,
+            canvas.width,
+            canvas.height,
+        );
+        assert!(
+            fits(offset.x, tile.width(), target.width())
+                && fits(offset.y, tile.height(), target.height()),
+            "a {}x{} tile doesn't fit in a {}x{} image at {:?}",
+            tile.width(),
+            tile.height(),
+            target.width(),
+            target.height(),
+            offset.to_tuple(),
+        );
+
+        let placement = self.placement(canvas);
+        // Each row is rendered on its own, so rows can be spread across threads without
+        // changing the result
+        #[cfg(not(feature = "parallel"))]
+        let rows = (tile.min_y()..tile.max_y()).map(|y| self.render_row(&placement, tile, y, sun));
+        #[cfg(feature = "parallel")]
+        let rows = {
+            use rayon::prelude::*;
+            (tile.min_y()..tile.max_y())
+                .into_par_iter()
+                .map(|y| self.render_row(&placement, tile, y, sun))
+                .collect::<Vec<_>>()
+        };
+
+        let mut stats = HashMap::new();
+        for (row, (colors, row_stats)) in rows.into_iter().enumerate() {
+            for (column, color) in colors.into_iter().enumerate() {
+                target.put_pixel(offset.x + column as u32, offset.y + row as u32, Rgba(color));
+            }
+            for (kind, count) in row_stats {
+                *stats.entry(kind).or_insert(0) += count;
+            }
+        }
+
+        stats
+    }
 
-        let center = Point2D::from_lengths(radius, radius);
+    /// Renders the pixels of row `y` inside `tile`, returning their colors and how many
+    /// pixels of each kind were rendered
+    fn render_row(
+        &self,
+        placement: &Placement,
+        tile: Rect<u32, Pixels>,
+        y: u32,
+        sun: &Option<Light>,
+    ) -> (Vec<[u8; 4]>, HashMap<Kind, u32>) {
         let mut stats = HashMap::new();
+        let colors = (tile.min_x()..tile.max_x())
+            .map(|x| {
+                let (color, kind) = self.render_pixel(placement, x, y, sun);
+                if let Some(kind) = kind {
+                    stats
+                        .entry(kind)
+                        .and_modify(|count| *count += 1)
+                        .or_insert(1);
+                }
+                color
+            })
+            .collect();
 
-        for (x, y, pixel) in image.enumerate_pixels_mut() {
-            let point = Point2D::new(x as f32, y as f32);
-            let distance = point.distance_to(center);
+        (colors, stats)
+    }
 
-            let planet_point =
-                point * planet_scale - Vector2D::from_lengths(self.radius, self.radius);
+    /// Works out where the planet sits on `canvas`, and how large it is
+    fn placement(&self, canvas: &Canvas) -> Placement {
+        let image_radius = Length::<f32, Pixels>::new(canvas.diameter() / 2.);
+        // Stars leave room around the disc for their corona, and irregular bodies for the
+        // parts that reach past the radius
+        let shape = self.shape.filter(|_| self.star.is_none());
+        let radius = match (&self.star, &shape) {
+            (Some(star), _) => image_radius * star.disc_scale(),
+            (None, Some(shape)) => image_radius / shape.max_extent(),
+            (None, None) => image_radius,
+        };
 
-            let color = if distance < radius.get() {
-                let (kind, color) = self.extrapolate_point(planet_point, sun);
-                // Inside the boundaries of the planet
-                let delta = radius.get() - distance;
-                let alpha = if delta < 1. {
-                    (255. * delta) as u8
-                } else {
-                    255
-                };
+        Placement {
+            center: canvas.center(),
+            radius,
+            planet_scale: self.radius / radius,
+            shape,
+        }
+    }
 
-                stats
-                    .entry(kind)
-                    .and_modify(|count| *count += 1)
-                    .or_insert(1);
+    /// Places the planet with its center at `center`, on a canvas where each pixel spans
+    /// `planet_scale`. This lets several bodies be drawn at the same scale.
+    pub(crate) fn placement_at(
+        &self,
+        center: Point2D<f32, Pixels>,
+        planet_scale: Scale<f32, Pixels, Kilometers>,
+    ) -> Placement {
+        Placement {
+            center,
+            radius: self.radius / planet_scale,
+            planet_scale,
+            shape: self.shape.filter(|_| self.star.is_none()),
+        }
+    }
 
-                [color.red as u8, color.green as u8, color.blue as u8, alpha]
+    /// Returns the color of the pixel at `x`, `y` on the canvas, and the kind of surface
+    /// it shows if it is on the planet
+    pub(crate) fn render_pixel(
+        &self,
+        placement: &Placement,
+        x: u32,
+        y: u32,
+        sun: &Option<Light>,
+    ) -> ([u8; 4], Option<Kind>) {
+        let Placement {
+            center,
+            radius,
+            planet_scale,
+            shape,
+        } = placement;
+        let point = Point2D::new(x as f32, y as f32);
+        let distance = point.distance_to(*center);
+
+        let planet_point = (point - center.to_vector()) * *planet_scale;
+
+        if let Some(shape) = shape {
+            match self.shape_coverage(shape, planet_point, *planet_scale) {
+                Some((hit, alpha)) => {
+                    let (kind, color) = self.extrapolate_shaped_point(&hit, sun);
+                    ([color.red, color.green, color.blue, alpha], Some(kind))
+                }
+                None => Default::default(),
+            }
+        } else if distance < radius.get() {
+            let (kind, color) = match &self.star {
+                Some(star) => self.extrapolate_star_point(star, planet_point),
+                None => self.extrapolate_point(planet_point, sun),
+            };
+            // Inside the boundaries of the planet
+            let delta = radius.get() - distance;
+            let alpha = if delta < 1. {
+                (255. * delta) as u8
             } else {
-                Default::default()
+                255
             };
 
-            *pixel = image::Rgba(color);
+            ([color.red, color.green, color.blue, alpha], Some(kind))
+        } else if let Some(star) = &self.star {
+            let color = self.corona_color(star, point - *center, distance / radius.get());
+            (color, None)
+        } else {
+            Default::default()
         }
-
-        GeneratedPlanet { image, stats }
     }
 }
+
+/// Where a planet sits on a canvas, and how large it is
+pub(crate) struct Placement {
+    /// The center of the planet on the canvas
+    center: Point2D<f32, Pixels>,
+
+    /// The radius of the planet's sphere on the canvas
+    radius: Length<f32, Pixels>,
+
+    /// How many kilometers each pixel spans
+    planet_scale: Scale<f32, Pixels, Kilometers>,
+
+    /// The shape of the planet if it is an irregular body
+    shape: Option<Shape>,
+}
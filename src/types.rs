@@ -1,4 +1,4 @@
-use euclid::Length;
+use euclid::{Angle, Length, Vector3D};
 
 /// Unit type for use in euclid geometric types to represent kilometers
 #[derive(Clone, Copy, Default)]
@@ -10,4 +10,62 @@ impl Kilometers {
     }
 }
 /// Unit type for use in euclid geometric types to represent pixel measurements
-pub(crate) struct Pixels;
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pixels;
+
+/// Unit type for use in euclid geometric types to represent points on a sphere with a radius of 1
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnitSphere;
+
+impl UnitSphere {
+    /// Returns `count` points spread evenly over the sphere, using a Fibonacci lattice
+    pub(crate) fn evenly_spaced(count: u32) -> impl Iterator<Item = Vector3D<f32, UnitSphere>> {
+        let golden_angle = std::f32::consts::PI * (3. - 5f32.sqrt());
+        (0..count).map(move |index| {
+            let y = 1. - (index as f32 + 0.5) / count as f32 * 2.;
+            let ring = (1. - y * y).sqrt();
+            let longitude = golden_angle * index as f32;
+            Vector3D::new(ring * longitude.sin(), y, ring * longitude.cos())
+        })
+    }
+}
+
+/// A position on the surface of a sphere. Latitude is measured from the equator towards
+/// the north pole, which is at the top of rendered images. Longitude 0 faces the viewer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialization",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct LatLong {
+    pub latitude: Angle<f32>,
+    pub longitude: Angle<f32>,
+}
+
+impl LatLong {
+    pub fn new(latitude: Angle<f32>, longitude: Angle<f32>) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Converts a point on the unit sphere to a latitude and longitude. Rendered images
+    /// have y pointing down and z pointing towards the viewer.
+    pub fn from_surface_point(surface_point: Vector3D<f32, UnitSphere>) -> Self {
+        Self {
+            latitude: Angle::radians((-surface_point.y).clamp(-1., 1.).asin()),
+            longitude: Angle::radians(surface_point.x.atan2(surface_point.z)),
+        }
+    }
+
+    /// Converts this position to a point on the unit sphere
+    pub fn to_surface_point(&self) -> Vector3D<f32, UnitSphere> {
+        let ring = self.latitude.radians.cos();
+        Vector3D::new(
+            ring * self.longitude.radians.sin(),
+            -self.latitude.radians.sin(),
+            ring * self.longitude.radians.cos(),
+        )
+    }
+}
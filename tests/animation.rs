@@ -0,0 +1,134 @@
+use magrathea::{euclid::Point2D, ElevationColor, Kilometers, Planet};
+use std::time::Duration;
+
+#[test]
+fn rotation_sprite_sheet() {
+    let planet = Planet::new_from_iter(
+        11,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    let sheet = planet.generate_rotation(16, 10, Duration::from_secs(2), &None);
+    assert_eq!(sheet.columns, 4);
+    assert_eq!(sheet.image.dimensions(), (64, 48));
+    assert_eq!(sheet.frame_duration, Duration::from_millis(200));
+
+    // The first frame is the planet as it is
+    let first = planet.generate(16, &None).image;
+    for (x, y, pixel) in first.enumerate_pixels() {
+        assert_eq!(sheet.image.get_pixel(x, y), pixel);
+    }
+    // and the rest have spun away from it
+    let (left, top) = sheet.frame_origin(5);
+    assert!(first
+        .enumerate_pixels()
+        .any(|(x, y, pixel)| sheet.image.get_pixel(left + x, top + y) != pixel));
+
+    #[cfg(feature = "serialization")]
+    {
+        let metadata: serde_json::Value =
+            serde_json::from_str(&sheet.metadata_json("planet \"1\".png")).unwrap();
+        let frames = metadata["frames"].as_array().unwrap();
+        assert_eq!(frames.len(), 10);
+        assert_eq!(frames[5]["frame"]["x"], 16);
+        assert_eq!(frames[5]["frame"]["y"], 16);
+        assert_eq!(frames[5]["duration"], 200);
+        assert_eq!(metadata["meta"]["image"], "planet \"1\".png");
+        assert_eq!(metadata["meta"]["size"]["w"], 64);
+        assert_eq!(metadata["meta"]["frameTags"][0]["to"], 9);
+    }
+}
+
+#[test]
+fn animated_formats() {
+    use magrathea::{
+        animation::AnimationMode,
+        image::{gif::GifDecoder, AnimationDecoder},
+        Light,
+    };
+
+    let planet = Planet::new_from_iter(
+        12,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    let sun = Some(Light::default());
+    let phase = planet.animate(AnimationMode::Phase, 16, 6, Duration::from_millis(50), &sun);
+    assert_eq!(phase.frames.len(), 6);
+    assert_ne!(phase.frames[0], phase.frames[3]);
+    // Phase and orbit move the light, but not the surface
+    let unlit = planet.animate(
+        AnimationMode::Orbit,
+        16,
+        6,
+        Duration::from_millis(50),
+        &None,
+    );
+    assert_eq!(unlit.frames[0], unlit.frames[3]);
+
+    let mut gif = Vec::new();
+    phase.write_gif(&mut gif).unwrap();
+    let frames = GifDecoder::new(gif.as_slice())
+        .unwrap()
+        .into_frames()
+        .collect_frames()
+        .unwrap();
+    assert_eq!(frames.len(), 6);
+    assert_eq!(frames[0].delay().numer_denom_ms(), (50, 1));
+
+    let mut apng = Vec::new();
+    phase.write_apng(&mut apng).unwrap();
+    let decoder = png::Decoder::new(apng.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let control = reader.info().animation_control.unwrap();
+    assert_eq!(control.num_frames, 6);
+    assert_eq!(control.num_plays, 0);
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let mut decoded = 0;
+    while reader.next_frame(&mut buffer).is_ok() {
+        let frame = reader.info().frame_control.unwrap();
+        assert_eq!((frame.delay_num, frame.delay_den), (50, 1000));
+        assert_eq!(buffer, phase.frames[decoded].as_raw().as_slice());
+        decoded += 1;
+    }
+    assert_eq!(decoded, 6);
+}
+
+#[test]
+fn orbit_follows_distance() {
+    use magrathea::{animation::AnimationMode, euclid::Angle, image::RgbaImage, Light, Orbit};
+
+    let orbit = Orbit {
+        eccentricity: 0.5,
+        ..Orbit::circular(
+            Kilometers::new(150_200_000.),
+            Angle::zero(),
+            Duration::from_secs(60),
+        )
+    };
+    let mut planet = Planet::new_from_iter(
+        12,
+        orbit.position_at(Duration::default()),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.orbit = Some(orbit);
+    let sun = Some(Light::default());
+    let brightness = |frame: &RgbaImage| {
+        frame
+            .pixels()
+            .map(|pixel| pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32)
+            .sum::<u32>()
+    };
+
+    let delay = Duration::from_millis(50);
+    let orbiting = planet.animate(AnimationMode::Orbit, 16, 6, delay, &sun);
+    // Nearest the star at the start, and farthest halfway around
+    assert!(brightness(&orbiting.frames[0]) > brightness(&orbiting.frames[3]));
+
+    // The light sweeps around at a fixed distance instead
+    let phase = planet.animate(AnimationMode::Phase, 16, 6, delay, &sun);
+    assert_ne!(phase.frames, orbiting.frames);
+}
@@ -0,0 +1,55 @@
+use magrathea::{
+    background::Nebula, euclid::Point2D, Background, ElevationColor, Kilometers, Light, Planet,
+};
+
+#[test]
+fn starfield() {
+    let background = Background {
+        seed: 9,
+        star_density: 0.01,
+        ..Default::default()
+    };
+    let image = background.generate(64, 32);
+    assert_eq!(image.dimensions(), (64, 32));
+    assert!(image.pixels().all(|pixel| pixel[3] == 255));
+
+    let space = image.get_pixel(0, 0);
+    let stars = image.pixels().filter(|pixel| *pixel != space).count();
+    assert!(stars > 0);
+    assert_eq!(image.into_raw(), background.generate(64, 32).into_raw());
+
+    let nebula = Background {
+        nebula: Some(Nebula {
+            coverage: 1.,
+            ..Default::default()
+        }),
+        ..background
+    };
+    assert_ne!(
+        nebula.generate(64, 32).into_raw(),
+        background.generate(64, 32).into_raw()
+    );
+}
+
+#[test]
+fn composite_under_planet() {
+    let planet = Planet::new_from_iter(
+        42,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    let generated = planet.generate(32, &Some(Light::default()));
+    let background = Background::new(1);
+    let composite = background.composite(&generated.image);
+
+    assert!(composite.pixels().all(|pixel| pixel[3] == 255));
+    assert_eq!(
+        composite.get_pixel(0, 0),
+        background.generate(32, 32).get_pixel(0, 0)
+    );
+    assert_eq!(
+        composite.get_pixel(16, 16),
+        generated.image.get_pixel(16, 16)
+    );
+}
@@ -0,0 +1,82 @@
+use magrathea::{
+    euclid::{Point2D, Vector3D},
+    Kilometers, Palette, Terrain, UnitSphere,
+};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// How much the elevation changes per radian along `path`
+fn variation<Kind>(
+    terrain: &Terrain<Kind>,
+    length: f32,
+    path: impl Iterator<Item = Vector3D<f32, UnitSphere>>,
+) -> f32
+where
+    Kind: magrathea::planet::SurfaceDefinition + Clone + std::hash::Hash + Eq,
+{
+    let elevations = path
+        .map(|point| terrain.elevation_at(point).get())
+        .collect::<Vec<_>>();
+    let total: f32 = elevations
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).abs())
+        .sum();
+    total / length
+}
+
+/// The elevation changes per radian along a circle of latitude and along a meridian
+fn variations<Kind>(terrain: &Terrain<Kind>) -> (f32, f32)
+where
+    Kind: magrathea::planet::SurfaceDefinition + Clone + std::hash::Hash + Eq,
+{
+    let steps = 512;
+    let step = |index: u32| index as f32 / steps as f32;
+    let latitude = 0.3f32;
+    let along_latitude = variation(
+        terrain,
+        TAU * latitude.cos(),
+        (0..=steps).map(|index| {
+            let longitude = TAU * step(index);
+            Vector3D::new(
+                latitude.cos() * longitude.cos(),
+                latitude.sin(),
+                latitude.cos() * longitude.sin(),
+            )
+        }),
+    );
+    let along_meridian = variation(
+        terrain,
+        PI,
+        (0..=steps).map(|index| {
+            let latitude = PI * step(index) - FRAC_PI_2;
+            Vector3D::new(latitude.cos(), latitude.sin(), 0.)
+        }),
+    );
+    (along_latitude, along_meridian)
+}
+
+#[test]
+fn bands_follow_latitude() {
+    let planet = Palette::preset("jupiter")
+        .unwrap()
+        .planet(3, Point2D::new(778_500_000., 0.), Kilometers::new(69_911.))
+        .unwrap();
+    assert!(planet.banding.is_some());
+
+    let (along_latitude, along_meridian) = variations(&Terrain::generate(&planet));
+    assert!(
+        along_meridian > along_latitude * 3.,
+        "{} {}",
+        along_meridian,
+        along_latitude
+    );
+
+    let mut unbanded = planet.clone();
+    unbanded.banding = None;
+    let (along_latitude, along_meridian) = variations(&Terrain::generate(&unbanded));
+    assert!(
+        along_meridian < along_latitude * 2.,
+        "{} {}",
+        along_meridian,
+        along_latitude
+    );
+}
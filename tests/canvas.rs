@@ -0,0 +1,53 @@
+use magrathea::{euclid::Point2D, Canvas, ElevationColor, Kilometers, Light, Planet};
+
+fn earth() -> Planet<magrathea::coloring::Earthlike> {
+    Planet::new_from_iter(
+        42,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    )
+}
+
+#[test]
+fn square_matches_generate() {
+    let planet = earth();
+    let sun = Some(Light::default());
+    assert_eq!(
+        planet
+            .generate_canvas(&Canvas::square(32), &sun)
+            .image
+            .into_raw(),
+        planet.generate(32, &sun).image.into_raw()
+    );
+
+    let padded = planet.generate_canvas(&Canvas::padded(32, 4), &sun);
+    assert_eq!(padded.image.dimensions(), (40, 40));
+    assert_eq!(padded.image.get_pixel(2, 20)[3], 0);
+    assert_eq!(padded.image.get_pixel(20, 20)[3], 255);
+}
+
+#[test]
+fn positioned_planet() {
+    let planet = earth();
+    let sun = Some(Light::default());
+    let square = planet.generate(40, &sun).image;
+    let canvas = Canvas {
+        width: 100,
+        height: 60,
+        diameter: Some(40),
+        center: Some(Point2D::new(30., 30.)),
+        padding: 0,
+    };
+    let placed = planet.generate_canvas(&canvas, &sun).image;
+    assert_eq!(placed.dimensions(), (100, 60));
+
+    for (x, y, pixel) in placed.enumerate_pixels() {
+        let inside = (10..50).contains(&x) && (10..50).contains(&y);
+        if inside {
+            assert_eq!(pixel, square.get_pixel(x - 10, y - 10));
+        } else {
+            assert_eq!(pixel[3], 0);
+        }
+    }
+}
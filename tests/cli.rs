@@ -0,0 +1,141 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// A definition with a seed too large for a TOML integer and a remap curve, which TOML
+/// can only store as a table
+const DEFINITION: &str = r##"{
+    "planet": {
+        "seed": 18446744073709551557,
+        "algorithm": "V1",
+        "max_chaos": 7.0,
+        "origin": [150200000.0, 0.0],
+        "radius": 6371.0,
+        "remap": { "Curve": [[0.0, 0.0], [0.5, 0.3], [1.0, 1.0]] },
+        "colors": [
+            { "kind": { "name": "Deep", "liquid": true }, "color": { "red": 0.1, "green": 0.1, "blue": 0.7 }, "elevation": -2000.0 },
+            { "kind": { "name": "Land" }, "color": { "red": 0.3, "green": 0.7, "blue": 0.2 }, "elevation": 200.0 },
+            { "kind": { "name": "Peak" }, "color": { "red": 0.9, "green": 0.9, "blue": 0.9 }, "elevation": 1600.0 }
+        ]
+    },
+    "resolution": 32
+}"##;
+
+/// A definition saved before palettes were added, which names `Earthlike` kinds
+const EARTHLIKE_DEFINITION: &str = "(
+    planet: (
+        seed: 13817164026409240319,
+        algorithm: V1,
+        max_chaos: 7,
+        origin: (106207440, 106207440),
+        radius: 6371,
+        colors: [
+            (kind: DeepOcean, color: (red: 0.07, green: 0.12, blue: 0.71), elevation: -2000),
+            (kind: ShallowOcean, color: (red: 0.38, green: 0.49, blue: 0.87), elevation: 0),
+            (kind: Grass, color: (red: 0.6, green: 0.84, blue: 0.4), elevation: 200),
+            (kind: Snow, color: (red: 0.93, green: 0.96, blue: 0.96), elevation: 1700),
+        ],
+        remap: Curve([(0, 0), (1, 1)]),
+        coverage: Some((kind: ShallowOcean, fraction: 0.7)),
+    ),
+    light: None,
+    resolution: Some(32),
+)";
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("magrathea-cli-{}-{}", std::process::id(), name))
+}
+
+/// Renders the definition at `path` to `output`, optionally saving it to `save`
+fn generate(path: &Path, output: &Path, save: Option<&Path>) {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_magrathea"));
+    command.arg("generate").arg("-o").arg(output);
+    if let Some(save) = save {
+        command.arg("-s").arg(save);
+    }
+    let result = command.arg("load").arg(path).output().unwrap();
+    assert!(
+        result.status.success(),
+        "{}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+}
+
+#[test]
+fn definitions_round_trip() {
+    let original = temp_path("original.json");
+    std::fs::write(&original, DEFINITION).unwrap();
+    let expected = temp_path("expected.png");
+    generate(&original, &expected, None);
+
+    for extension in &["ron", "toml", "json"] {
+        let saved = temp_path(&format!("saved.{}", extension));
+        let output = temp_path(&format!("{}.png", extension));
+        generate(&original, &temp_path("ignored.png"), Some(&saved));
+        generate(&saved, &output, None);
+        assert_eq!(
+            std::fs::read(&output).unwrap(),
+            std::fs::read(&expected).unwrap(),
+            "{}",
+            extension
+        );
+        std::fs::remove_file(&saved).unwrap();
+        std::fs::remove_file(&output).unwrap();
+    }
+
+    for path in &[original, expected, temp_path("ignored.png")] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn earthlike_definitions_load() {
+    let original = temp_path("earthlike.ron");
+    std::fs::write(&original, EARTHLIKE_DEFINITION).unwrap();
+    let saved = temp_path("earthlike.json");
+    let output = temp_path("earthlike.png");
+    generate(&original, &output, Some(&saved));
+
+    let saved_json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&saved).unwrap()).unwrap();
+    let planet = &saved_json["planet"];
+    assert_eq!(planet["seed"], "13817164026409240319");
+    assert_eq!(
+        planet["colors"][1]["kind"],
+        serde_json::json!({ "name": "ShallowOcean", "liquid": true })
+    );
+    assert_eq!(planet["colors"][2]["kind"]["liquid"], false);
+    assert_eq!(planet["coverage"]["kind"]["name"], "ShallowOcean");
+
+    // The converted definition renders the same planet
+    let converted = temp_path("converted.png");
+    generate(&saved, &converted, None);
+    assert_eq!(
+        std::fs::read(&converted).unwrap(),
+        std::fs::read(&output).unwrap()
+    );
+
+    for path in &[original, saved, output, converted] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn invalid_period() {
+    for period in &["-1", "NaN", "inf"] {
+        let result = Command::new(env!("CARGO_BIN_EXE_magrathea"))
+            .arg("animate")
+            .arg("-o")
+            .arg(temp_path("period.gif"))
+            .arg(format!("--period={}", period))
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        assert!(!result.status.success(), "{}", period);
+        assert!(stderr.contains("period"), "{}", stderr);
+        assert!(!stderr.contains("panicked"), "{}", stderr);
+    }
+}
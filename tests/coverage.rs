@@ -0,0 +1,68 @@
+use magrathea::{
+    coloring::Earthlike,
+    euclid::{Point2D, Vector3D},
+    Coverage, ElevationColor, GenerationAlgorithm, Kilometers, Planet, Terrain,
+};
+
+/// The fraction of the sphere covered by `kind` or a lower kind
+fn measured_coverage(planet: &Planet<Earthlike>, kind: Earthlike) -> f32 {
+    let terrain = Terrain::generate(planet);
+    let highest = ElevationColor::earthlike()
+        .iter()
+        .position(|elevation| elevation.kind == kind)
+        .unwrap();
+    let covered = |found: &Earthlike| {
+        ElevationColor::earthlike()[..=highest]
+            .iter()
+            .any(|elevation| elevation.kind == *found)
+    };
+
+    // A Fibonacci lattice, denser than the one the solver samples
+    let count = 20_000;
+    let golden_angle = std::f32::consts::PI * (3. - 5f32.sqrt());
+    let covered_count = (0..count)
+        .filter(|&index| {
+            let y = 1. - (index as f32 + 0.5) / count as f32 * 2.;
+            let radius = (1. - y * y).sqrt();
+            let (sin, cos) = (golden_angle * index as f32).sin_cos();
+            covered(
+                &terrain
+                    .elevation_color_at(Vector3D::new(cos * radius, y, sin * radius))
+                    .kind,
+            )
+        })
+        .count();
+    covered_count as f32 / count as f32
+}
+
+#[test]
+fn target_fraction_is_reached() {
+    for algorithm in [GenerationAlgorithm::V0, GenerationAlgorithm::V1] {
+        for (seed, kind, fraction) in [
+            (1, Earthlike::ShallowOcean, 0.7),
+            (2, Earthlike::ShallowOcean, 0.2),
+            (3, Earthlike::Grass, 0.5),
+            (4, Earthlike::DeepOcean, 0.35),
+        ] {
+            let mut planet = Planet::new_from_iter(
+                seed,
+                Point2D::new(150_200_000., 0.),
+                Kilometers::new(6_371.),
+                ElevationColor::earthlike(),
+            );
+            planet.algorithm = algorithm;
+            planet.coverage = Some(Coverage::new(kind.clone(), fraction));
+
+            let measured = measured_coverage(&planet, kind.clone());
+            assert!(
+                (measured - fraction).abs() < 0.02,
+                "{:?} seed {} {:?}: {} instead of {}",
+                algorithm,
+                seed,
+                kind,
+                measured,
+                fraction
+            );
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use magrathea::{
+    craters::Crater,
+    euclid::{Point2D, Vector3D},
+    Craters, ElevationColor, Kilometers, Planet,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const MOON_RADIUS: f32 = 1_737.;
+
+#[test]
+fn placement() {
+    let craters = Craters {
+        count: 500,
+        min_radius: Kilometers::new(10.),
+        max_radius: Kilometers::new(200.),
+        ..Craters::default()
+    };
+    let placed = craters.place(
+        &mut ChaCha8Rng::seed_from_u64(1),
+        Kilometers::new(MOON_RADIUS),
+    );
+    assert_eq!(placed.len(), 500);
+    for crater in &placed {
+        assert!((crater.center.length() - 1.).abs() < 1e-4);
+        assert!(crater.radius >= craters.min_radius && crater.radius <= craters.max_radius);
+    }
+
+    // Largest first, and far more small craters than large ones
+    for pair in placed.windows(2) {
+        assert!(pair[0].radius >= pair[1].radius);
+    }
+    let small = placed
+        .iter()
+        .filter(|crater| crater.radius.get() < 20.)
+        .count();
+    assert!(small > placed.len() / 2, "{} small craters", small);
+
+    let again = craters.place(
+        &mut ChaCha8Rng::seed_from_u64(1),
+        Kilometers::new(MOON_RADIUS),
+    );
+    for (a, b) in placed.iter().zip(&again) {
+        assert_eq!(a.center, b.center);
+        assert_eq!(a.radius, b.radius);
+    }
+}
+
+#[test]
+fn bowl_and_rim() {
+    let radius = Kilometers::new(MOON_RADIUS);
+    let crater = Crater {
+        center: Vector3D::new(0., 0., 1.),
+        radius: Kilometers::new(100.),
+    };
+    // A point `distance` crater radii from the center
+    let at = |craters: &Craters, distance: f32| {
+        let angle = distance * crater.radius.get() / MOON_RADIUS;
+        let point = Vector3D::new(angle.sin(), 0., angle.cos());
+        craters.elevation_change(&crater, point, radius).get()
+    };
+
+    let craters = Craters::default();
+    let center = at(&craters, 0.);
+    let rim = at(&craters, 1.);
+    assert!(center < 0., "{}", center);
+    assert!(rim > 0., "{}", rim);
+    assert!(at(&craters, 0.5) > center && at(&craters, 0.5) < rim);
+    assert_eq!(at(&craters, 3.5), 0.);
+
+    // Ejecta spreads the rim's material further out
+    let without_ejecta = Craters {
+        ejecta: 0.,
+        ..craters
+    };
+    let with_ejecta = Craters {
+        ejecta: 1.,
+        ..craters
+    };
+    assert!(at(&with_ejecta, 2.) > at(&without_ejecta, 2.));
+}
+
+#[test]
+fn craters_dent_the_surface() {
+    let moon = |craters: Option<Craters>| {
+        let mut planet = Planet::new_from_iter(
+            3,
+            Point2D::new(150_200_000., 0.),
+            Kilometers::new(MOON_RADIUS),
+            ElevationColor::moonlike(),
+        );
+        planet.craters = craters;
+        planet.generate(48, &None).image.into_raw()
+    };
+    assert_ne!(moon(None), moon(Some(Craters::default())));
+    assert_eq!(
+        moon(Some(Craters::default())),
+        moon(Some(Craters::default()))
+    );
+}
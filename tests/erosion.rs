@@ -0,0 +1,59 @@
+use magrathea::{
+    coloring::Earthlike,
+    erosion::ElevationGrid,
+    euclid::{Length, Point2D, Vector3D},
+    ElevationColor, Erosion, Kilometers, Planet, Terrain, UnitSphere,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn erosion() -> Erosion {
+    Erosion {
+        resolution: 64,
+        droplets: 2_000,
+        thermal_iterations: 5,
+        ..Erosion::default()
+    }
+}
+
+/// Rolling hills with a few ridges, for the droplets to carve into
+fn hills(point: Vector3D<f32, UnitSphere>) -> Length<f32, Kilometers> {
+    Kilometers::new(((point.x * 7.).sin() + (point.y * 5.).cos() * (point.z * 3.).sin()) * 800.)
+}
+
+fn bits(grid: &ElevationGrid) -> Vec<u32> {
+    grid.elevations
+        .iter()
+        .map(|elevation| elevation.to_bits())
+        .collect()
+}
+
+#[test]
+fn same_seed_same_grid() {
+    let first = erosion().erode(&mut ChaCha8Rng::seed_from_u64(4), hills);
+    let second = erosion().erode(&mut ChaCha8Rng::seed_from_u64(4), hills);
+    assert_eq!((first.width, first.height), (second.width, second.height));
+    assert_eq!(bits(&first), bits(&second));
+
+    let other_seed = erosion().erode(&mut ChaCha8Rng::seed_from_u64(5), hills);
+    assert_ne!(bits(&first), bits(&other_seed));
+
+    // Erosion changes the surface it starts from
+    let uneroded = ElevationGrid::from_fn(first.width, first.height, hills);
+    assert_ne!(bits(&first), bits(&uneroded));
+}
+
+#[test]
+fn same_planet_same_grid() {
+    let mut planet = Planet::new_from_iter(
+        9,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::<Earthlike>::earthlike(),
+    );
+    planet.erosion = Some(erosion());
+
+    let first = Terrain::generate(&planet).elevation_grid.unwrap();
+    let second = Terrain::generate(&planet).elevation_grid.unwrap();
+    assert_eq!(bits(&first), bits(&second));
+}
@@ -0,0 +1,103 @@
+//! Golden-image tests guarding seed stability. Each test renders a planet from a fixed
+//! seed and compares a hash of the pixels against the value recorded when the current
+//! `GenerationAlgorithm` was released. If one of these fails, the change being tested
+//! alters existing planets and needs to be released as a new `GenerationAlgorithm`.
+
+use magrathea::{
+    coloring::Earthlike, euclid::Point2D, star::Star, Coverage, Craters, ElevationColor,
+    ElevationRemap, Erosion, GenerationAlgorithm, Kilometers, Light, Planet, Rivers, Tectonics,
+};
+
+const RESOLUTION: u32 = 64;
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is guaranteed to never change
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn earthlike(seed: u64) -> Planet<Earthlike> {
+    let mut planet = Planet::new_from_iter(
+        seed,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.algorithm = GenerationAlgorithm::V1;
+    planet
+}
+
+fn image_hash<Kind>(planet: &Planet<Kind>, sun: &Option<Light>) -> u64
+where
+    Kind: magrathea::planet::SurfaceDefinition
+        + magrathea::planet::ParallelSafe
+        + Clone
+        + std::hash::Hash
+        + Eq,
+{
+    fnv1a(&planet.generate(RESOLUTION, sun).image.into_raw())
+}
+
+#[test]
+fn unlit() {
+    assert_eq!(image_hash(&earthlike(1), &None), 0xf1e1342f57c42dd5);
+    assert_eq!(image_hash(&earthlike(42), &None), 0x50d1869d4b380cb5);
+    assert_eq!(image_hash(&earthlike(u64::MAX), &None), 0x724237cfcb84aebd);
+}
+
+#[test]
+fn lit() {
+    let sun = Some(Light::from_u8(255, 240, 220, 1.));
+    assert_eq!(image_hash(&earthlike(1), &sun), 0xdcbde249fe17439e);
+    assert_eq!(image_hash(&earthlike(42), &sun), 0x30d09ea9d9b78681);
+}
+
+/// Optional features aren't covered by the `GenerationAlgorithm`, so this hash is
+/// recorded again when one of them changes
+#[test]
+fn features() {
+    let mut planet = earthlike(7);
+    planet.craters = Some(Craters::default());
+    planet.tectonics = Some(Tectonics::default());
+    planet.remap = ElevationRemap::Equalized;
+    planet.coverage = Some(Coverage::new(Earthlike::ShallowOcean, 0.6));
+    assert_eq!(image_hash(&planet, &None), 0xf46e8104f2058b6a);
+}
+
+/// Recorded again when erosion or rivers change, like `features`
+#[test]
+fn erosion_and_rivers() {
+    let mut planet = earthlike(9);
+    planet.erosion = Some(Erosion {
+        resolution: 64,
+        droplets: 2_000,
+        thermal_iterations: 5,
+        ..Erosion::default()
+    });
+    planet.rivers = Some(Rivers::earthlike());
+    assert_eq!(image_hash(&planet, &None), 0x4842b5a49c9186b0);
+}
+
+#[test]
+fn star() {
+    let star = Planet::new_star(3, Kilometers::new(696_340.), Star::default());
+    assert_eq!(image_hash(&star, &None), 0x3eb07d82d471017f);
+}
+
+/// Recorded before generation was versioned. `SmallRng` differs between platforms, so
+/// these are only checked on the platform they were recorded on.
+#[test]
+fn original_algorithm() {
+    let original = |seed| Planet {
+        algorithm: GenerationAlgorithm::V0,
+        ..earthlike(seed)
+    };
+    assert_eq!(image_hash(&original(1), &None), 0x5c7eb3daef8629ab);
+
+    let light = |sols| Some(Light::from_u8(255, 240, 220, sols));
+    assert_eq!(image_hash(&original(1), &light(0.5)), 0x710ed0640ea535e9);
+    assert_eq!(image_hash(&original(1), &light(3.)), 0x5ab0af10331b536f);
+    assert_eq!(image_hash(&original(42), &light(0.5)), 0x9d613262594ddc7e);
+    assert_eq!(image_hash(&original(42), &light(3.)), 0xf636081e9a3aeedc);
+}
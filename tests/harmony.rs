@@ -0,0 +1,62 @@
+use magrathea::{
+    coloring::{Earthlike, Harmony},
+    palette::{Lab, Lch},
+    ElevationColor,
+};
+
+fn earthlike_kinds() -> impl Iterator<
+    Item = (
+        Earthlike,
+        magrathea::euclid::Length<f32, magrathea::Kilometers>,
+    ),
+> {
+    ElevationColor::earthlike()
+        .into_iter()
+        .map(|color| (color.kind, color.elevation))
+}
+
+#[test]
+fn seeded() {
+    let harmony = Harmony::default();
+    let first = harmony.generate(7, earthlike_kinds());
+    let second = harmony.generate(7, earthlike_kinds());
+    let other = harmony.generate(8, earthlike_kinds());
+    for (first, second) in first.iter().zip(&second) {
+        assert_eq!(first.kind, second.kind);
+        assert_eq!(first.color, second.color);
+    }
+    assert!(first
+        .iter()
+        .zip(&other)
+        .any(|(first, other)| first.color != other.color));
+}
+
+#[test]
+fn readable_steps() {
+    let harmony = Harmony::default();
+    for seed in 0..50 {
+        let colors = harmony.generate(seed, earthlike_kinds());
+        assert_eq!(colors.len(), 7);
+        let lightness = colors
+            .iter()
+            .map(|color| Lch::from(Lab::from(color.color.into_linear())).l)
+            .collect::<Vec<_>>();
+        // Two liquids followed by five kinds of land, each ramp getting lighter
+        for ramp in &[&lightness[..2], &lightness[2..]] {
+            for pair in ramp.windows(2) {
+                assert!(pair[1] - pair[0] > harmony.lightness_step - 0.5);
+            }
+        }
+    }
+}
+
+#[test]
+fn random_palette_names() {
+    let palette = magrathea::Palette::random(3);
+    assert_eq!(palette.colors[0].kind, "Deep Liquid");
+    assert!(palette.colors[0].liquid);
+
+    // Debug output stays quoted, unlike the names written to palettes
+    let kind = magrathea::DynamicKind::new("Deep Liquid", true);
+    assert_eq!(format!("{:?}", kind), "\"Deep Liquid\"");
+}
@@ -0,0 +1,99 @@
+use magrathea::{
+    coloring::Earthlike,
+    euclid::{Angle, Point2D},
+    ElevationColor, Kilometers, Light, Moon, Orbit, Planet, Shape,
+};
+use std::time::Duration;
+
+const MONTH: Duration = Duration::from_secs(28 * 24 * 60 * 60);
+
+fn earth_with_moon(orbit: Orbit) -> Planet<Earthlike> {
+    let mut planet = Planet::new_from_iter(
+        42,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    let moon = Planet::new_from_iter(
+        7,
+        Point2D::default(),
+        Kilometers::new(1_737.),
+        vec![
+            ElevationColor::from_u8(Earthlike::Mountain, 255, 0, 255, Kilometers::new(-100.)),
+            ElevationColor::from_u8(Earthlike::Snow, 255, 0, 255, Kilometers::new(100.)),
+        ],
+    );
+    planet.moons.push(Moon::new(moon, orbit));
+    planet
+}
+
+fn is_moon(pixel: &image::Rgba<u8>) -> bool {
+    pixel[0] > 200 && pixel[1] < 50 && pixel[2] > 200
+}
+
+#[test]
+fn depth_order() {
+    // Seen edge-on, the moon passes in front of and then behind the planet
+    let orbit = Orbit {
+        inclination: Angle::degrees(90.),
+        ..Orbit::circular(Kilometers::new(20_000.), Angle::zero(), MONTH)
+    };
+    let planet = earth_with_moon(orbit);
+
+    let in_front = planet.generate_with_moons(64, MONTH / 4, &None);
+    assert!(is_moon(in_front.image.get_pixel(32, 32)));
+    assert!(in_front.stats.contains_key(&Earthlike::Snow));
+
+    let behind = planet.generate_with_moons(64, MONTH * 3 / 4, &None);
+    assert!(!is_moon(behind.image.get_pixel(32, 32)));
+    assert!(!behind.stats.contains_key(&Earthlike::Snow));
+}
+
+#[test]
+fn eclipse() {
+    let planet = earth_with_moon(Orbit::circular(
+        Kilometers::new(20_000.),
+        Angle::zero(),
+        MONTH,
+    ));
+    let sun = Some(Light::default());
+    let brightness = |time: Duration, x: u32, y: u32| {
+        let generated = planet.generate_with_moons(64, time, &sun);
+        let pixel = generated.image.get_pixel(x, y);
+        pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32
+    };
+
+    // Directly behind the planet, the moon is in its shadow. A quarter orbit later it isn't.
+    let (radius, scale) = (20_000. + 1_737., 32.);
+    let offset = (20_000. / radius * scale) as u32;
+    let eclipsed = brightness(Duration::default(), 32 + offset, 32);
+    let lit = brightness(MONTH / 4, 32, 32 + offset);
+    assert!(eclipsed * 2 < lit, "{} vs {}", eclipsed, lit);
+}
+
+#[test]
+fn shaped_moon() {
+    let mut planet = earth_with_moon(Orbit::circular(
+        Kilometers::new(20_000.),
+        Angle::zero(),
+        MONTH,
+    ));
+    planet.moons[0].planet.shape = Some(Shape {
+        elongation: 2.5,
+        roughness: 0.,
+        ..Shape::default()
+    });
+
+    let generated = planet.generate_with_moons(128, Duration::default(), &None);
+    let image = &generated.image;
+    let moon_at = |x: u32, y: u32| is_moon(image.get_pixel(x, y));
+    let width = (0..128)
+        .filter(|&x| (0..128).any(|y| moon_at(x, y)))
+        .count();
+    let height = (0..128)
+        .filter(|&y| (0..128).any(|x| moon_at(x, y)))
+        .count();
+    // Drawn as a sphere, the moon would be as tall as it is wide
+    assert!(width > height * 2, "{} x {}", width, height);
+    assert!(generated.stats.contains_key(&Earthlike::Snow));
+}
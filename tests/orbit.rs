@@ -0,0 +1,72 @@
+use magrathea::{
+    euclid::{Angle, Point2D},
+    planet::calculate_origin,
+    ElevationColor, Kilometers, Light, Orbit, Planet,
+};
+use std::time::Duration;
+
+const YEAR: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() <= b.abs() * 1e-4, "{} != {}", a, b);
+}
+
+#[test]
+fn circular_matches_calculate_origin() {
+    let distance = Kilometers::new(150_200_000.);
+    let orbit = Orbit::circular(distance, Angle::degrees(30.), YEAR);
+    let expected = calculate_origin(Angle::degrees(120.), distance);
+    let position = orbit.position_at(YEAR / 4);
+    assert_close(position.x, expected.x);
+    assert_close(position.y, expected.y);
+    assert_close(orbit.insolation_at(YEAR / 3), 1.);
+}
+
+#[test]
+fn elliptical() {
+    let orbit = Orbit {
+        eccentricity: 0.5,
+        argument_of_periapsis: Angle::degrees(90.),
+        ..Orbit::circular(Kilometers::new(100_000_000.), Angle::zero(), YEAR)
+    };
+
+    // Closest to the star at periapsis, and farthest half an orbit later
+    let periapsis = orbit.position_at(Duration::default());
+    assert_close(periapsis.y, 50_000_000.);
+    assert!(periapsis.x.abs() < 100.);
+    let apoapsis = orbit.position_at(YEAR / 2);
+    assert_close(apoapsis.y, -150_000_000.);
+    assert_close(orbit.insolation_at(Duration::default()), 4.);
+    assert_close(orbit.insolation_at(YEAR / 2), 4. / 9.);
+
+    // Inclined orbits are squashed when seen from above
+    let inclined = Orbit {
+        inclination: Angle::degrees(60.),
+        ..orbit
+    };
+    assert_close(inclined.position_at(Duration::default()).y, 25_000_000.);
+}
+
+#[test]
+fn planet_at_time() {
+    let mut planet = Planet::new_from_iter(
+        3,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.orbit = Some(Orbit {
+        eccentricity: 0.2,
+        ..Orbit::circular(Kilometers::new(150_200_000.), Angle::zero(), YEAR)
+    });
+    planet.rotation_period = Some(Duration::from_secs(24 * 60 * 60));
+
+    let later = planet.at_time(Duration::from_secs(6 * 60 * 60));
+    assert_close(later.rotation.radians, std::f32::consts::FRAC_PI_2);
+    assert_ne!(later.origin, planet.at_time(Duration::default()).origin);
+
+    // The light is brightest at periapsis and dimmest at apoapsis
+    let light = Light::default();
+    assert!(planet.light_at(Duration::default(), light).sols > light.sols);
+    assert!(planet.light_at(YEAR / 2, light).sols < light.sols);
+}
@@ -0,0 +1,92 @@
+use magrathea::{
+    coloring::{Earthlike, PaletteEntry, PaletteFormat},
+    palette::Srgb,
+    ElevationColor, Kilometers, Palette,
+};
+
+fn entries() -> Vec<PaletteEntry> {
+    vec![
+        PaletteEntry {
+            color: Srgb::new(19, 30, 180),
+            name: Some("Deep Ocean".to_string()),
+        },
+        PaletteEntry {
+            color: Srgb::new(238, 246, 245),
+            name: Some("Snow".to_string()),
+        },
+        PaletteEntry {
+            color: Srgb::new(92, 64, 51),
+            name: None,
+        },
+    ]
+}
+
+#[test]
+fn round_trip() {
+    for format in &[PaletteFormat::Gimp, PaletteFormat::Hex, PaletteFormat::Jasc] {
+        let written = format.write("test", &entries());
+        let parsed = format.parse(&written).unwrap();
+        let colors = parsed.iter().map(|entry| entry.color).collect::<Vec<_>>();
+        assert_eq!(
+            colors,
+            entries()
+                .iter()
+                .map(|entry| entry.color)
+                .collect::<Vec<_>>()
+        );
+        if *format == PaletteFormat::Gimp {
+            assert_eq!(parsed, entries());
+            assert!(written.ends_with(" 92  64  51\t\n"));
+        }
+    }
+}
+
+#[test]
+fn parse_tool_output() {
+    let gimp = "GIMP Palette\nName: Test\nColumns: 4\n#\n# A comment\n 19  30 180\tDeep Ocean\n238 246 245\n";
+    let parsed = PaletteFormat::Gimp.parse(gimp).unwrap();
+    assert_eq!(parsed[0].name.as_deref(), Some("Deep Ocean"));
+    assert_eq!(parsed[1].name, None);
+
+    let hex = "131eb4\r\nEEF6F5\r\n";
+    assert_eq!(
+        PaletteFormat::Hex.parse(hex).unwrap()[1].color,
+        Srgb::new(238, 246, 245)
+    );
+
+    let jasc = "JASC-PAL\r\n0100\r\n2\r\n19 30 180\r\n238 246 245 255\r\n";
+    assert_eq!(
+        PaletteFormat::Jasc.parse(jasc).unwrap()[0].color,
+        Srgb::new(19, 30, 180)
+    );
+    assert!(PaletteFormat::Jasc
+        .parse("JASC-PAL\r\n0100\r\n3\r\n19 30 180\r\n")
+        .is_err());
+}
+
+#[test]
+fn elevation_colors() {
+    let path = std::env::temp_dir().join(format!("magrathea-{}.gpl", std::process::id()));
+    ElevationColor::save_palette(&ElevationColor::earthlike(), &path).unwrap();
+
+    let kinds = ElevationColor::earthlike()
+        .into_iter()
+        .map(|color| (color.kind, color.elevation));
+    let loaded = ElevationColor::<Earthlike>::load_palette(&path, kinds).unwrap();
+    for (loaded, original) in loaded.iter().zip(ElevationColor::earthlike()) {
+        assert_eq!(loaded.kind, original.kind);
+        assert_eq!(loaded.color, original.color);
+    }
+
+    let elevations = [Kilometers::new(-100.), Kilometers::new(100.)];
+    assert!(Palette::import(&path, &elevations).is_err());
+    let elevations = ElevationColor::earthlike()
+        .iter()
+        .map(|color| color.elevation)
+        .collect::<Vec<_>>();
+    let palette = Palette::import(&path, &elevations).unwrap();
+    assert_eq!(palette.colors[0].kind, "DeepOcean");
+    assert_eq!(palette.colors[0].color, "#131eb4");
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,69 @@
+//! With the `parallel` feature, rows are rendered on many threads at once. Rendering one
+//! row at a time leaves nothing to spread across threads, so it must match exactly. The
+//! golden tests also run with the feature on, comparing against hashes from serial renders.
+#![cfg(feature = "parallel")]
+
+use magrathea::{
+    coloring::{Earthlike, Moonlike},
+    euclid::{Point2D, Rect, Size2D},
+    image::RgbaImage,
+    planet::{ParallelSafe, SurfaceDefinition},
+    star::Star,
+    Canvas, Craters, ElevationColor, Kilometers, Light, Planet, Rivers, Shape, Tectonics, Terrain,
+};
+use std::{collections::HashMap, hash::Hash};
+
+fn assert_matches_serial<Kind>(planet: &Planet<Kind>, sun: &Option<Light>)
+where
+    Kind: SurfaceDefinition + ParallelSafe + Clone + Hash + Eq + std::fmt::Debug,
+{
+    let canvas = Canvas::padded(48, 4);
+    let expected = planet.generate_canvas(&canvas, sun);
+
+    let terrain = Terrain::generate(planet);
+    let mut image = RgbaImage::new(canvas.width, canvas.height);
+    let mut stats = HashMap::new();
+    for y in 0..canvas.height {
+        let row = Rect::new(Point2D::new(0, y), Size2D::new(canvas.width, 1));
+        let row_stats = terrain.render_tile(&canvas, row, &mut image, Point2D::new(0, y), sun);
+        for (kind, count) in row_stats {
+            *stats.entry(kind).or_insert(0) += count;
+        }
+    }
+
+    assert_eq!(stats, expected.stats);
+    assert_eq!(image.into_raw(), expected.image.into_raw());
+}
+
+#[test]
+fn planet_matches_serial() {
+    let mut planet = Planet::<Earthlike>::new_from_iter(
+        11,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.craters = Some(Craters::default());
+    planet.tectonics = Some(Tectonics::default());
+    planet.rivers = Some(Rivers::earthlike());
+    assert_matches_serial(&planet, &Some(Light::default()));
+    assert_matches_serial(&planet, &None);
+}
+
+#[test]
+fn shaped_body_matches_serial() {
+    let mut planet = Planet::<Moonlike>::new_from_iter(
+        5,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(20.),
+        ElevationColor::moonlike(),
+    );
+    planet.shape = Some(Shape::default());
+    assert_matches_serial(&planet, &Some(Light::default()));
+}
+
+#[test]
+fn star_matches_serial() {
+    let star = Planet::new_star(3, Kilometers::new(696_340.), Star::default());
+    assert_matches_serial(&star, &None);
+}
@@ -0,0 +1,46 @@
+use magrathea::{
+    euclid::Point2D, palette::Srgb, quantize::Dithering, ElevationColor, Kilometers, Light, Planet,
+    Quantize,
+};
+
+#[test]
+fn only_palette_colors() {
+    let planet = Planet::new_from_iter(
+        5,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    let original = planet.generate(48, &Some(Light::default())).image;
+    let palette = Quantize::pico8().palette;
+
+    for dithering in &[
+        Dithering::None,
+        Dithering::FloydSteinberg,
+        Dithering::Ordered,
+    ] {
+        let mut image = original.clone();
+        Quantize {
+            dithering: *dithering,
+            ..Quantize::pico8()
+        }
+        .apply(&mut image);
+
+        for (quantized, original) in image.pixels().zip(original.pixels()) {
+            assert_eq!(quantized[3], original[3]);
+            if original[3] == 0 {
+                assert_eq!(quantized, original);
+            } else {
+                assert!(palette.contains(&Srgb::new(quantized[0], quantized[1], quantized[2])));
+            }
+        }
+    }
+}
+
+#[test]
+fn nearest_is_perceptual() {
+    let mut image =
+        magrathea::image::RgbaImage::from_pixel(1, 1, magrathea::image::Rgba([250, 20, 90, 255]));
+    Quantize::pico8().apply(&mut image);
+    assert_eq!(image.get_pixel(0, 0).0, [0xff, 0x00, 0x4d, 255]);
+}
@@ -0,0 +1,78 @@
+use magrathea::{
+    coloring::Earthlike, euclid::Point2D, ElevationColor, ElevationRemap, GenerationAlgorithm,
+    Kilometers, Planet,
+};
+use std::collections::HashMap;
+
+fn earthlike(seed: u64, remap: ElevationRemap) -> Planet<Earthlike> {
+    let mut planet = Planet::new_from_iter(
+        seed,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.remap = remap;
+    planet
+}
+
+/// The share of the rendered pixels of each kind, over a few seeds
+fn shares(planet: impl Fn(u64) -> Planet<Earthlike>) -> HashMap<Earthlike, f32> {
+    let mut counts = HashMap::new();
+    for seed in 0..4 {
+        for (kind, count) in planet(seed).generate(64, &None).stats {
+            *counts.entry(kind).or_insert(0) += count;
+        }
+    }
+    let total = counts.values().sum::<u32>() as f32;
+    counts
+        .into_iter()
+        .map(|(kind, count)| (kind, count as f32 / total))
+        .collect()
+}
+
+#[test]
+fn equalized_shows_every_stop() {
+    let stops = ElevationColor::earthlike().len();
+    for algorithm in [GenerationAlgorithm::V0, GenerationAlgorithm::V1] {
+        let shares = shares(|seed| Planet {
+            algorithm,
+            ..earthlike(seed, ElevationRemap::Equalized)
+        });
+        assert_eq!(shares.len(), stops, "{:?}", algorithm);
+        for (kind, share) in shares {
+            assert!(
+                (share - 1. / stops as f32).abs() < 0.05,
+                "{:?} {:?}: {}",
+                algorithm,
+                kind,
+                share
+            );
+        }
+    }
+
+    // Without remapping, the extremes rarely appear
+    let linear = shares(|seed| earthlike(seed, ElevationRemap::Linear));
+    assert!(linear.get(&Earthlike::Snow).copied().unwrap_or_default() < 0.05);
+}
+
+#[test]
+fn curve_maps_elevations() {
+    // A flat curve puts the whole surface at one elevation
+    let lowest = earthlike(1, ElevationRemap::Curve(vec![(0., 0.), (1., 0.)]));
+    let stats = lowest.generate(32, &None).stats;
+    assert_eq!(stats.keys().collect::<Vec<_>>(), [&Earthlike::DeepOcean]);
+
+    let highest = earthlike(1, ElevationRemap::Curve(vec![(0., 1.), (1., 1.)]));
+    let stats = highest.generate(32, &None).stats;
+    assert_eq!(stats.keys().collect::<Vec<_>>(), [&Earthlike::Snow]);
+
+    // The identity curve changes nothing
+    let identity = earthlike(1, ElevationRemap::Curve(vec![(0., 0.), (1., 1.)]));
+    assert_eq!(
+        identity.generate(32, &None).image.into_raw(),
+        earthlike(1, ElevationRemap::Linear)
+            .generate(32, &None)
+            .image
+            .into_raw()
+    );
+}
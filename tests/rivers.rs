@@ -0,0 +1,63 @@
+use magrathea::{
+    coloring::Earthlike,
+    euclid::{Length, Point2D, Vector3D},
+    ElevationColor, Kilometers, Planet, Rivers, Terrain, UnitSphere,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[test]
+fn rivers_flow_to_liquid() {
+    let rivers = Rivers {
+        count: 20,
+        ..Rivers::earthlike()
+    };
+    // Highest at the north pole, with liquid across the southern hemisphere
+    let elevation_at = |point: Vector3D<f32, UnitSphere>| Kilometers::new(-point.y * 2000.);
+    let is_liquid = |elevation: Length<f32, Kilometers>| elevation.get() < 0.;
+    let traced = rivers.trace(
+        &mut ChaCha8Rng::seed_from_u64(1),
+        Kilometers::new(6_371.),
+        elevation_at,
+        is_liquid,
+    );
+    assert!(!traced.is_empty());
+
+    let step = rivers.step.get() / 6_371.;
+    for (index, river) in traced.iter().enumerate() {
+        let source = elevation_at(river.path[0].to_surface_point());
+        assert!(source >= rivers.source_elevation);
+
+        // Each river ends in liquid or where it joins an earlier river
+        let mouth = river.path.last().unwrap().to_surface_point();
+        let joins = traced[..index].iter().any(|other| {
+            other
+                .path
+                .iter()
+                .any(|point| (point.to_surface_point() - mouth).length() < step * 2.)
+        });
+        assert!(is_liquid(elevation_at(mouth)) || joins);
+    }
+}
+
+#[test]
+fn generated_rivers_are_rendered() {
+    let mut planet = Planet::new_from_iter(
+        5,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.rivers = Some(Rivers::earthlike());
+
+    let generated = planet.generate(128, &None);
+    assert!(!generated.rivers.is_empty());
+    assert!(generated.stats.get(&Earthlike::River).copied().unwrap_or(0) > 0);
+
+    // The rivers returned with the image are the ones the terrain traces
+    let traced = Terrain::generate(&planet).rivers;
+    assert_eq!(generated.rivers.len(), traced.len());
+    for (generated, traced) in generated.rivers.iter().zip(&traced) {
+        assert_eq!(generated.path, traced.path);
+    }
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "serialization")]
+
+use magrathea::{
+    coloring::Earthlike, euclid::Point2D, planet::SurfaceDefinition, Coverage, Craters,
+    DynamicKind, ElevationColor, ElevationRemap, Kilometers, Light, Palette, Planet, Rivers,
+    Tectonics,
+};
+
+#[test]
+fn planet_round_trip() {
+    let mut planet = Planet::new_from_iter(
+        42,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.craters = Some(Craters::default());
+    planet.tectonics = Some(Tectonics::default());
+    planet.remap = ElevationRemap::Curve(vec![(0., 0.), (0.5, 0.4), (1., 1.)]);
+    planet.coverage = Some(Coverage::new(Earthlike::ShallowOcean, 0.7));
+    planet.rivers = Some(Rivers::earthlike());
+
+    let json = serde_json::to_string(&planet).unwrap();
+    let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert_eq!(value["radius"], 6_371.);
+    assert_eq!(value["colors"][0]["elevation"], -2_000.);
+
+    let loaded = serde_json::from_str::<Planet<Earthlike>>(&json).unwrap();
+    let sun = Some(Light::default());
+    assert_eq!(
+        planet.generate(32, &sun).image.into_raw(),
+        loaded.generate(32, &sun).image.into_raw()
+    );
+}
+
+/// Every format the CLI saves definitions in can store every setting. TOML can't store
+/// enum variants with data, such as `ElevationRemap::Curve`, which the CLI writes through
+/// JSON instead and `tests/cli.rs` covers.
+#[test]
+#[cfg(feature = "cli")]
+fn formats_round_trip() {
+    // Larger than TOML's largest integer
+    let mut planet = Planet::new_from_iter(
+        u64::MAX - 58,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.craters = Some(Craters::default());
+    planet.remap = ElevationRemap::Equalized;
+    planet.coverage = Some(Coverage::new(Earthlike::ShallowOcean, 0.7));
+    let expected = planet.generate(32, &None).image.into_raw();
+
+    let ron = ron::to_string(&planet).unwrap();
+    let loaded = ron::from_str::<Planet<Earthlike>>(&ron).unwrap();
+    assert_eq!(loaded.generate(32, &None).image.into_raw(), expected);
+
+    // TOML requires plain values to come before tables, which converting to a
+    // `toml::Value` first takes care of
+    let toml = toml::to_string(&toml::Value::try_from(&planet).unwrap()).unwrap();
+    let loaded = toml::from_str::<Planet<Earthlike>>(&toml).unwrap();
+    assert_eq!(loaded.generate(32, &None).image.into_raw(), expected);
+
+    let json = serde_json::to_string(&planet).unwrap();
+    let loaded = serde_json::from_str::<Planet<Earthlike>>(&json).unwrap();
+    assert_eq!(loaded.generate(32, &None).image.into_raw(), expected);
+}
+
+#[test]
+fn optional_settings_default() {
+    let loaded = serde_json::from_str::<Planet<Earthlike>>(
+        r#"{
+            "seed": 1,
+            "algorithm": "V1",
+            "max_chaos": 7.0,
+            "origin": [150200000.0, 0.0],
+            "radius": 6371.0,
+            "colors": [
+                { "kind": "Grass", "color": { "red": 0.0, "green": 1.0, "blue": 0.0 }, "elevation": 100.0 },
+                { "kind": "DeepOcean", "color": { "red": 0.0, "green": 0.0, "blue": 1.0 }, "elevation": -100.0 }
+            ],
+            "craters": { "count": 10 }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(loaded.colors[0].kind, Earthlike::DeepOcean);
+    assert_eq!(loaded.craters.unwrap().count, 10);
+    assert_eq!(
+        loaded.craters.unwrap().exponent,
+        Craters::default().exponent
+    );
+    assert!(loaded.rivers.is_none());
+}
+
+#[test]
+fn algorithm_is_required() {
+    let error = serde_json::from_str::<Planet<Earthlike>>(
+        r#"{
+            "seed": 1,
+            "max_chaos": 7.0,
+            "origin": [150200000.0, 0.0],
+            "radius": 6371.0,
+            "colors": []
+        }"#,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("algorithm"), "{}", error);
+}
+
+#[test]
+fn dynamic_palette() {
+    let palette = serde_json::from_str::<Palette>(
+        r##"{
+            "max_chaos": 5.0,
+            "colors": [
+                { "kind": "Crystal Plains", "color": "#9fd8e8", "elevation": 250.0 },
+                { "kind": "Brine Sea", "liquid": true, "color": "1b2a5e", "elevation": -1500.0 }
+            ]
+        }"##,
+    )
+    .unwrap();
+    let planet = palette
+        .planet(3, Point2D::new(150_200_000., 0.), Kilometers::new(6_371.))
+        .unwrap();
+    assert_eq!(planet.max_chaos, 5.);
+    // Palettes don't share their chaos
+    let calm = Palette {
+        max_chaos: 1.5,
+        ..palette.clone()
+    };
+    let calm_planet = calm
+        .planet(3, Point2D::new(150_200_000., 0.), Kilometers::new(6_371.))
+        .unwrap();
+    assert_eq!(calm_planet.max_chaos, 1.5);
+    assert_eq!(
+        palette
+            .planet(3, Point2D::new(150_200_000., 0.), Kilometers::new(6_371.))
+            .unwrap()
+            .max_chaos,
+        5.
+    );
+    assert_eq!(planet.colors[0].kind, DynamicKind::new("Brine Sea", true));
+    assert!(planet.colors[0].kind.is_liquid());
+    assert_eq!(planet.colors[1].kind.name(), "Crystal Plains");
+
+    let json = serde_json::to_string(&planet).unwrap();
+    let loaded = serde_json::from_str::<Planet<DynamicKind>>(&json).unwrap();
+    assert_eq!(loaded.colors[0].kind, planet.colors[0].kind);
+    assert_eq!(
+        planet.generate(32, &None).image.into_raw(),
+        loaded.generate(32, &None).image.into_raw()
+    );
+
+    let earthlike = Palette::from_colors(ElevationColor::earthlike());
+    assert_eq!(earthlike.colors[0].kind, "DeepOcean");
+    assert_eq!(earthlike.colors[0].color, "#131eb4");
+    assert!(earthlike.colors[0].liquid);
+}
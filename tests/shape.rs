@@ -0,0 +1,76 @@
+use magrathea::{
+    coloring::Moonlike, euclid::Point2D, Craters, ElevationColor, Kilometers, Light, Planet, Shape,
+};
+
+fn asteroid(seed: u64) -> Planet<Moonlike> {
+    let mut planet = Planet::new_from_iter(
+        seed,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(20.),
+        ElevationColor::moonlike(),
+    );
+    planet.shape = Some(Shape::default());
+    planet.craters = Some(Craters {
+        count: 30,
+        min_radius: Kilometers::new(1.),
+        max_radius: Kilometers::new(6.),
+        ..Craters::default()
+    });
+    planet
+}
+
+fn opaque_extent(image: &magrathea::image::RgbaImage) -> (u32, u32) {
+    let opaque = |x: u32, y: u32| image.get_pixel(x, y)[3] > 0;
+    let width = (0..image.width())
+        .filter(|&x| (0..image.height()).any(|y| opaque(x, y)))
+        .count() as u32;
+    let height = (0..image.height())
+        .filter(|&y| (0..image.width()).any(|x| opaque(x, y)))
+        .count() as u32;
+    (width, height)
+}
+
+#[test]
+fn elongated_silhouette() {
+    let planet = asteroid(5);
+    let sun = Some(Light::default());
+    let generated = planet.generate(48, &sun);
+    let (width, height) = opaque_extent(&generated.image);
+    assert!(width > height, "{} x {}", width, height);
+    assert!(generated.stats.values().sum::<u32>() > 0);
+    assert_eq!(
+        generated.image.into_raw(),
+        planet.generate(48, &sun).image.into_raw()
+    );
+
+    let mut sphere = planet.clone();
+    sphere.shape = None;
+    let (width, height) = opaque_extent(&sphere.generate(48, &sun).image);
+    assert_eq!(width, height);
+}
+
+#[test]
+fn distinct_bodies() {
+    let sun = Some(Light::default());
+    assert_ne!(
+        asteroid(1).generate(32, &sun).image.into_raw(),
+        asteroid(2).generate(32, &sun).image.into_raw()
+    );
+
+    let mut rotated = asteroid(1);
+    rotated.rotation = magrathea::euclid::Angle::degrees(90.);
+    let (width, height) = opaque_extent(&rotated.generate(32, &sun).image);
+    // Turned end-on, the long axis points at the viewer
+    assert!(width <= height + 4, "{} x {}", width, height);
+}
+
+#[test]
+fn antialiased_silhouette() {
+    let generated = asteroid(5).generate(48, &Some(Light::default()));
+    let alphas = generated.image.pixels().map(|pixel| pixel[3]);
+    assert!(alphas.clone().any(|alpha| alpha == 255));
+    assert!(alphas.clone().any(|alpha| alpha > 0 && alpha < 255));
+    // Partly covered pixels only appear along the edge
+    let partial = alphas.filter(|&alpha| alpha > 0 && alpha < 255).count();
+    assert!(partial < generated.stats.values().sum::<u32>() as usize / 2);
+}
@@ -0,0 +1,85 @@
+use magrathea::{
+    coloring::{Earthlike, Sunlike},
+    euclid::Point2D,
+    image::RgbaImage,
+    star::{Star, SOLAR_RADIUS},
+    ElevationColor, Kilometers, Light, Planet,
+};
+
+fn brightness(planet: &Planet<Earthlike>, light: Light) -> u64 {
+    // Compare only how much is lit, not the color of the light
+    let light = Light {
+        color: Light::default().color,
+        ..light
+    };
+    planet
+        .generate(48, &Some(light))
+        .image
+        .pixels()
+        .map(|pixel| pixel[0] as u64 + pixel[1] as u64 + pixel[2] as u64)
+        .sum()
+}
+
+#[test]
+fn hotter_star_lights_more() {
+    let planet = Planet::new_from_iter(
+        1,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    let radius = Kilometers::new(SOLAR_RADIUS);
+    let cool = Star::with_temperature(3_000.).light(radius);
+    let hot = Star::with_temperature(9_000.).light(radius);
+    assert!(hot.sols > 1. && cool.sols < 1.);
+
+    let dim = brightness(&planet, cool);
+    let sunlit = brightness(&planet, Light::default());
+    let bright = brightness(&planet, hot);
+    assert!(dim < sunlit, "{} < {}", dim, sunlit);
+    assert!(sunlit < bright, "{} < {}", sunlit, bright);
+}
+
+/// A star rendered 96 pixels wide, and the radius of its disc in pixels
+fn rendered_star() -> (RgbaImage, f32) {
+    let star = Star::default();
+    let disc_radius = 48. / (1. + star.corona);
+    let planet: Planet<Sunlike> = Planet::new_star(3, Kilometers::new(SOLAR_RADIUS), star);
+    (planet.generate(96, &None).image, disc_radius)
+}
+
+/// The pixels `fraction` of the disc radius from the center, straight up, down, left and right
+fn ring(image: &RgbaImage, disc_radius: f32, fraction: f32) -> Vec<image::Rgba<u8>> {
+    let offset = (disc_radius * fraction) as u32;
+    let center = image.width() / 2;
+    vec![
+        *image.get_pixel(center + offset, center),
+        *image.get_pixel(center - offset, center),
+        *image.get_pixel(center, center + offset),
+        *image.get_pixel(center, center - offset),
+    ]
+}
+
+#[test]
+fn disc_edge_is_darker() {
+    let (image, disc_radius) = rendered_star();
+    let brightness = |fraction: f32| -> u32 {
+        ring(&image, disc_radius, fraction)
+            .iter()
+            .map(|pixel| pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32)
+            .sum()
+    };
+    let center = brightness(0.);
+    let edge = brightness(0.95);
+    assert!(edge < center, "{} < {}", edge, center);
+}
+
+#[test]
+fn corona_surrounds_disc() {
+    let (image, disc_radius) = rendered_star();
+    for pixel in ring(&image, disc_radius, 1.05) {
+        assert!(pixel[3] > 0, "{:?}", pixel);
+    }
+    // The corona fades out before the edge of the image
+    assert_eq!(image.get_pixel(0, 0)[3], 0);
+}
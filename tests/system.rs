@@ -0,0 +1,77 @@
+use magrathea::{
+    system::{BodyKind, System},
+    Light,
+};
+use std::time::Duration;
+
+#[test]
+fn kinds_follow_distance() {
+    for seed in 0..20 {
+        let system = System::generate(seed);
+        assert!((3..=8).contains(&system.bodies.len()));
+        assert!(system.star.star.is_some());
+
+        let order = |kind: BodyKind| match kind {
+            BodyKind::Rocky => 0,
+            BodyKind::GasGiant => 1,
+            BodyKind::Ice => 2,
+        };
+        for pair in system.bodies.windows(2) {
+            assert!(pair[0].distance() < pair[1].distance());
+            assert!(order(pair[0].kind) <= order(pair[1].kind));
+        }
+    }
+}
+
+#[test]
+fn deterministic_overview() {
+    let system = System::generate(1);
+    assert_eq!(
+        System::generate(1).bodies[0].planet.seed,
+        system.bodies[0].planet.seed
+    );
+    assert!(system.light().sols > 0.);
+    assert_ne!(system.light().color, Light::default().color);
+
+    let overview = system.render_overview(32, Duration::default());
+    assert_eq!(overview.height(), 32 + 4 * 2);
+    assert_eq!(
+        overview.into_raw(),
+        System::generate(1)
+            .render_overview(32, Duration::default())
+            .into_raw()
+    );
+}
+
+#[test]
+fn overview_lit_by_distance() {
+    // The hottest and coolest stars of the first few seeds
+    for seed in [4, 12] {
+        let system = System::generate(seed);
+        let pixels = 32;
+        let overview = system.render_overview(pixels, Duration::default());
+
+        // Skip past the star, then count how much of the bodies is lit
+        let bodies_start = pixels + pixels / 8 * 2;
+        let (mut lit, mut opaque) = (0, 0);
+        for (x, _, pixel) in overview.enumerate_pixels() {
+            if x >= bodies_start && pixel[3] == 255 {
+                opaque += 1;
+                if pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32 > 30 {
+                    lit += 1;
+                }
+            }
+        }
+        let lit = lit as f32 / opaque as f32;
+        assert!(lit > 0.15 && lit < 0.85, "seed {}: {} lit", seed, lit);
+    }
+}
+
+#[test]
+fn tiny_overview() {
+    let system = System::generate(1);
+    for pixels in 0..4 {
+        let overview = system.render_overview(pixels, Duration::default());
+        assert_eq!(overview.height(), pixels + (pixels / 8).max(1) * 2);
+    }
+}
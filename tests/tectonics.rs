@@ -0,0 +1,71 @@
+use magrathea::{
+    coloring::Earthlike, euclid::Point2D, planet::SurfaceDefinition, Craters, ElevationColor,
+    Kilometers, Planet, Tectonics, Terrain,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn earthlike(seed: u64) -> Planet<Earthlike> {
+    let mut planet = Planet::new_from_iter(
+        seed,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    );
+    planet.tectonics = Some(Tectonics::default());
+    planet
+}
+
+#[test]
+fn continental_fraction() {
+    let continents = |fraction: f32| {
+        let tectonics = Tectonics {
+            plates: 40,
+            continental_fraction: fraction,
+            ..Tectonics::default()
+        };
+        tectonics
+            .place(&mut ChaCha8Rng::seed_from_u64(1))
+            .iter()
+            .filter(|plate| plate.continental)
+            .count()
+    };
+    assert_eq!(continents(0.), 0);
+    assert_eq!(continents(1.), 40);
+    assert_eq!(continents(-1.), 0);
+    assert_eq!(continents(2.), 40);
+    assert_eq!(continents(f32::NAN), 0);
+}
+
+#[test]
+fn plates_are_independent_of_craters() {
+    let plates = Terrain::generate(&earthlike(3)).plates;
+    let mut cratered = earthlike(3);
+    cratered.craters = Some(Craters::default());
+    let cratered_plates = Terrain::generate(&cratered).plates;
+
+    assert_eq!(plates.len(), cratered_plates.len());
+    for (plate, cratered) in plates.iter().zip(&cratered_plates) {
+        assert_eq!(plate.center, cratered.center);
+        assert_eq!(plate.rotation, cratered.rotation);
+        assert_eq!(plate.continental, cratered.continental);
+    }
+}
+
+#[test]
+fn plates_shape_the_surface() {
+    let mut planet = earthlike(5);
+    planet.tectonics = Some(Tectonics {
+        blend: 1.,
+        ..Tectonics::default()
+    });
+    let terrain = Terrain::generate(&planet);
+    assert!(terrain.plates.iter().any(|plate| plate.continental));
+    assert!(terrain.plates.iter().any(|plate| !plate.continental));
+
+    // With the plates alone, continental plates rise above the ocean
+    for plate in &terrain.plates {
+        let kind = &terrain.elevation_color_at(plate.center).kind;
+        assert_eq!(kind.is_liquid(), !plate.continental, "{:?}", plate);
+    }
+}
@@ -0,0 +1,80 @@
+use magrathea::{
+    euclid::{Point2D, Rect, Size2D},
+    image::{GenericImage, RgbaImage},
+    Canvas, ElevationColor, Kilometers, Light, Planet, Terrain,
+};
+
+fn earth() -> Planet<magrathea::coloring::Earthlike> {
+    Planet::new_from_iter(
+        42,
+        Point2D::new(150_200_000., 0.),
+        Kilometers::new(6_371.),
+        ElevationColor::earthlike(),
+    )
+}
+
+#[test]
+fn render_into_atlas() {
+    let planet = earth();
+    let sun = Some(Light::default());
+    let expected = planet.generate(32, &sun);
+
+    let mut atlas = RgbaImage::new(64, 48);
+    let stats = planet.render_into(&Canvas::square(32), &mut atlas, Point2D::new(24, 8), &sun);
+    assert_eq!(stats, expected.stats);
+    assert_eq!(
+        atlas.sub_image(24, 8, 32, 32).to_image().into_raw(),
+        expected.image.into_raw()
+    );
+    assert_eq!(atlas.get_pixel(0, 0)[3], 0);
+}
+
+#[test]
+fn tiles_match_whole_image() {
+    let planet = earth();
+    let sun = Some(Light::default());
+    let canvas = Canvas::padded(40, 4);
+    let expected = planet.generate_canvas(&canvas, &sun);
+
+    let terrain = Terrain::generate(&planet);
+    let mut image = RgbaImage::new(canvas.width, canvas.height);
+    let mut total = 0;
+    for y in (0..canvas.height).step_by(16) {
+        for x in (0..canvas.width).step_by(16) {
+            let size = Size2D::new(16.min(canvas.width - x), 16.min(canvas.height - y));
+            let mut tile = RgbaImage::new(size.width, size.height);
+            let stats = terrain.render_tile(
+                &canvas,
+                Rect::new(Point2D::new(x, y), size),
+                &mut tile,
+                Point2D::zero(),
+                &sun,
+            );
+            total += stats.values().sum::<u32>();
+            image.copy_from(&tile, x, y).unwrap();
+        }
+    }
+
+    assert_eq!(total, expected.stats.values().sum::<u32>());
+    assert_eq!(image.into_raw(), expected.image.into_raw());
+}
+
+#[test]
+#[should_panic(expected = "isn't inside")]
+fn tile_outside_canvas() {
+    let terrain = Terrain::generate(&earth());
+    let canvas = Canvas::square(32);
+    let mut tile = RgbaImage::new(16, 16);
+    let outside = Rect::new(Point2D::new(24, 0), Size2D::new(16, 16));
+    terrain.render_tile(&canvas, outside, &mut tile, Point2D::zero(), &None);
+}
+
+#[test]
+#[should_panic(expected = "doesn't fit")]
+fn offset_past_target() {
+    let terrain = Terrain::generate(&earth());
+    let canvas = Canvas::square(32);
+    let mut tile = RgbaImage::new(16, 16);
+    let inside = Rect::new(Point2D::zero(), Size2D::new(16, 16));
+    terrain.render_tile(&canvas, inside, &mut tile, Point2D::new(u32::MAX, 0), &None);
+}